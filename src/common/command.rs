@@ -0,0 +1,102 @@
+use job::{Job, JobType};
+use definitions::{ElementReference, TAG_PREFIX};
+
+/*
+    The textual command syntax shared by rlctl and the command hotkeys of the compositor:
+    [PRIORITY_TAG] COMMAND [TAGS/ARGS]
+*/
+
+pub fn parse_job_type(repr: &str) -> Option<JobType>{
+    match repr
+    {
+        "focus" => Some(JobType::FOCUS),
+        "runapp" => Some(JobType::RUN_APP),
+        "tree" => Some(JobType::SEND_TREE),
+        "moveto" => Some(JobType::MOVE_TO),
+        "swap" => Some(JobType::SWAP),
+        "resize" => Some(JobType::RESIZE),
+        "split" => Some(JobType::SPLIT),
+        "presel" => Some(JobType::PRESELECT),
+        "close" => Some(JobType::CLOSE),
+        "kill" => Some(JobType::KILL),
+        "fullscreen" => Some(JobType::FULLSCREEN),
+        "maximize" => Some(JobType::MAXIMIZE),
+        "float" => Some(JobType::FLOAT),
+        "scratchpad" => Some(JobType::SCRATCHPAD),
+        "rules" => Some(JobType::RULES),
+        "master" => Some(JobType::MASTER),
+        "tab" => Some(JobType::TAB),
+        "policy" => Some(JobType::POLICY),
+        "layout" => Some(JobType::LAYOUT_SNAPSHOT),
+        "session" => Some(JobType::SESSION),
+        "compact" => Some(JobType::COMPACT),
+        "subscribe" => Some(JobType::SUBSCRIBE),
+        "reload" => Some(JobType::CONFIG_RELOAD),
+        "workspace" => Some(JobType::WORKSPACE_INSERT),
+        _ => None
+    }
+} 
+
+pub fn job_from_args<I: Iterator<Item = String>>(mut args: I) -> Result<Job, String>{
+    let mut job = Job::default();
+
+    if let Some(head_tag_or_cmd) = args.next(){
+        if head_tag_or_cmd.contains(TAG_PREFIX){
+            job.main_ref = Some(ElementReference::Tag(head_tag_or_cmd.trim().replace(TAG_PREFIX, "")));
+        }
+        else{
+            match parse_job_type(&head_tag_or_cmd){
+                Some(job_type) => job.job_type = job_type,
+                None => return Err(format!("Unknown command: {}", head_tag_or_cmd))
+            }
+        }
+    }
+    else{
+        return Err(String::from("No arguments given."));
+    }
+    
+    if job.main_ref.is_some(){
+        if let Some(command) = args.next(){
+            match parse_job_type(&command){
+                Some(job_type) => job.job_type = job_type,
+                None => return Err(format!("Unknown command: {}", command))
+            }
+        }
+        else{
+            return Err(String::from("No command given."));
+        }
+    }
+
+    while let Some(arg) = args.next(){
+        if arg.contains(TAG_PREFIX){
+            if job.main_ref.is_none(){
+                job.main_ref = Some(ElementReference::Tag(arg.replace(TAG_PREFIX, "")));
+            }
+            else{
+                job.contextual_refs.push(ElementReference::Tag(arg.replace(TAG_PREFIX, "")));
+            }
+        }
+        else{
+            job.anonymous_args.push(arg);
+        }
+    }
+
+    // Commands taking a verb are resolved once all arguments are known
+    if job.job_type == JobType::WORKSPACE_INSERT {
+        match job.anonymous_args.first().cloned() {
+            Some(ref verb) if verb == "add" => { job.anonymous_args.remove(0); },
+            Some(ref verb) if verb == "remove" => { 
+                job.anonymous_args.remove(0); 
+                job.job_type = JobType::WORKSPACE_REMOVE;
+            },
+            _ => return Err(String::from("Usage: [@WORKSPACE] workspace add|remove [row|column]"))
+        }
+    }
+
+    Ok(job)
+}
+
+/// Parses a command written as a single line, e.g. "@firefox moveto @mid_mid"
+pub fn job_from_line(line: &str) -> Result<Job, String>{
+    job_from_args(line.split_whitespace().map(|arg| arg.to_string()))
+}
@@ -28,9 +28,13 @@ pub const LEFT_CLICK: u32 = 0x110;
 pub const RIGHT_CLICK: u32 = 0x111;
 
 pub const SOCKET_PORT: u16 = 4451;
-pub const SOCKET_DETERMINANT: u8 = b'$';
+pub const SOCKET_ENV_VAR: &str = "RUSTLAND_SOCKET";
+pub const SOCKET_FILE_PREFIX: &str = "rustland";
+pub const SOCKET_FILE_MODE: u32 = 0o600;
+pub const SOCKET_DIR_MODE: u32 = 0o700;
 pub const TAG_PREFIX: &str = "@";
 pub const PROPERTY_PREFIX: &str = "~";
 
 pub const FPS: u64 = 60;
+pub const COMPACTION_DELAY: u64 = 3000;
 pub const FALLBACK_RESOLUTION: Size = Size { w: 640, h: 480 };
\ No newline at end of file
@@ -0,0 +1,37 @@
+use std::fmt;
+
+use definitions::{LayoutElemID, ViewPID};
+
+// Notifications pushed to clients that have subscribed through JobType::SUBSCRIBE
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub enum Event{
+    ViewCreated { element_id: LayoutElemID, pid: ViewPID, title: String, app_id: String },
+    ViewDestroyed { element_id: LayoutElemID, pid: ViewPID },
+    Focus { element_id: LayoutElemID, pid: Option<ViewPID> },
+    Workspace { index: usize, element_id: LayoutElemID },
+    Workspaces { columns: usize, rows: usize },
+    Tag { tag: String, element_ids: Vec<LayoutElemID> },
+    ConfigReload { success: bool }
+}
+
+impl Event{
+    // The name used by clients to filter the events they are interested in
+    pub fn name(&self) -> &'static str{
+        match *self
+        {
+            Event::ViewCreated { .. } => "view_created",
+            Event::ViewDestroyed { .. } => "view_destroyed",
+            Event::Focus { .. } => "focus",
+            Event::Workspace { .. } => "workspace",
+            Event::Workspaces { .. } => "workspaces",
+            Event::Tag { .. } => "tag",
+            Event::ConfigReload { .. } => "config_reload"
+        }
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { 
+        write!(f, "{}", self.name())
+    }
+}
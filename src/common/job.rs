@@ -9,9 +9,29 @@ pub enum JobType{
     FOCUS,
     LAYOUT_REFRESH,
     WORKSPACE_INSERT,
+    WORKSPACE_REMOVE,
     RUN_APP,
     SEND_TREE,
-    MOVE_TO
+    MOVE_TO,
+    SWAP,
+    RESIZE,
+    SPLIT,
+    PRESELECT,
+    CLOSE,
+    KILL,
+    FULLSCREEN,
+    MAXIMIZE,
+    FLOAT,
+    SCRATCHPAD,
+    RULES,
+    MASTER,
+    TAB,
+    POLICY,
+    LAYOUT_SNAPSHOT,
+    SESSION,
+    COMPACT,
+    SUBSCRIBE,
+    CONFIG_RELOAD
 }
 
 impl fmt::Display for JobType {
@@ -22,9 +42,29 @@ impl fmt::Display for JobType {
             JobType::FOCUS => "Switch the focus to a specific element/location",
             JobType::LAYOUT_REFRESH => "Refresh the layout for new conditions",
             JobType::WORKSPACE_INSERT => "Insert a workspace at the focused location",
+            JobType::WORKSPACE_REMOVE => "Remove an empty workspace",
             JobType::RUN_APP => "Run a specific application",
             JobType::SEND_TREE => "Show a tree view over the layout",
-            JobType::MOVE_TO => "Move an element to a specific location"
+            JobType::MOVE_TO => "Move an element to a specific location",
+            JobType::SWAP => "Exchange the places of two windows",
+            JobType::RESIZE => "Grow or shrink a window along one of its edges",
+            JobType::SPLIT => "Change the orientation of a split",
+            JobType::PRESELECT => "Choose where the next window is placed",
+            JobType::CLOSE => "Ask windows to close",
+            JobType::KILL => "Close windows, forcefully if they don't comply in time",
+            JobType::FULLSCREEN => "Let a window cover the whole output",
+            JobType::MAXIMIZE => "Let a window fill its workspace",
+            JobType::FLOAT => "Move a window between the tiling and the floating layer",
+            JobType::SCRATCHPAD => "Hide a window in the scratchpad or summon it from there",
+            JobType::RULES => "Show which window rules apply to a window",
+            JobType::MASTER => "Adjust the master area of a master-stack workspace",
+            JobType::TAB => "Switch between, create or dissolve tabs",
+            JobType::POLICY => "Choose the layout policy of a workspace",
+            JobType::LAYOUT_SNAPSHOT => "Save the layout to disk or restore a saved one",
+            JobType::SESSION => "Save the session or relaunch the applications of the saved one",
+            JobType::COMPACT => "Clean up what closed windows left behind in the layout",
+            JobType::SUBSCRIBE => "Stream events of the compositor to the client",
+            JobType::CONFIG_RELOAD => "Reload the configuration file"
         })
     }
 }
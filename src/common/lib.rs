@@ -1,9 +1,13 @@
 #[macro_use]
 extern crate serde_derive;
+extern crate serde;
 extern crate serde_json;
 
 pub mod definitions;
 pub mod job;
+pub mod event;
+pub mod protocol;
+pub mod command;
 
 pub use serde_derive::*;
 pub use serde_json::*;
\ No newline at end of file
@@ -0,0 +1,133 @@
+use std::io::{self, Read, Write, ErrorKind};
+
+use serde::ser::Serialize;
+use serde::de::Deserialize;
+use serde_json;
+
+/*
+    Wire format shared by rustland and rlctl: 
+
+    Every message is a frame consisting of a 4 byte (big endian) length header followed by 
+    that many bytes of JSON. A connection starts with both peers sending a Handshake frame 
+    and is dropped if their protocol versions differ.
+*/
+
+// Bump whenever the format of a frame or of a serialized type (Job, Event...) changes
+pub const PROTOCOL_VERSION: u32 = 1;
+
+// The upper limit for a frame - a broken or foreign peer shouldn't make us allocate arbitrarily 
+pub const MAX_FRAME_LENGTH: usize = 16 * 1024 * 1024;
+
+const HEADER_LENGTH: usize = 4;
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct Handshake{
+    pub version: u32
+}
+
+impl Handshake{
+    pub fn current() -> Handshake{
+        Handshake{
+            version: PROTOCOL_VERSION
+        }
+    }
+}
+
+pub fn write_frame<W: Write>(writer: &mut W, payload: &[u8]) -> io::Result<()>{
+    if payload.len() > MAX_FRAME_LENGTH {
+        return Err(io::Error::new(ErrorKind::InvalidInput, "Frame exceeds the maximum length."));
+    }
+
+    let length = payload.len() as u32;
+    let header = [(length >> 24) as u8, (length >> 16) as u8, (length >> 8) as u8, length as u8];
+
+    writer.write_all(&header)?;
+    writer.write_all(payload)?;
+    writer.flush()
+}
+
+/// Reads the next frame, or None when the peer closed the connection in between frames
+pub fn read_frame<R: Read>(reader: &mut R) -> io::Result<Option<Vec<u8>>>{
+    let mut header = [0u8; HEADER_LENGTH];
+    let mut received = 0;
+
+    while received < HEADER_LENGTH {
+        match reader.read(&mut header[received..])? {
+            0 if received == 0 => return Ok(None),
+            0 => return Err(io::Error::new(ErrorKind::UnexpectedEof, "Connection closed within a frame header.")),
+            n => received += n
+        }
+    }
+
+    let length = 
+        (header[0] as usize) << 24 | 
+        (header[1] as usize) << 16 | 
+        (header[2] as usize) << 8 | 
+        header[3] as usize;
+
+    if length > MAX_FRAME_LENGTH {
+        return Err(io::Error::new(ErrorKind::InvalidData, format!("Frame of {} bytes exceeds the maximum length - is the peer speaking the same protocol?", length)));
+    }
+
+    let mut payload = vec![0u8; length];
+    reader.read_exact(&mut payload)?;
+
+    Ok(Some(payload))
+}
+
+pub fn write_message<W: Write, T: Serialize>(writer: &mut W, message: &T) -> Result<(), String>{
+    match serde_json::to_vec(message) {
+        Ok(payload) => write_frame(writer, &payload).map_err(|e| format!("Couldn't write message: {}", e)),
+        Err(e) => Err(format!("Couldn't serialize message: {}", e))
+    }
+}
+
+/// Reads and deserializes the next frame, or None when the peer closed the connection
+pub fn read_message<R: Read, T: Deserialize>(reader: &mut R) -> Result<Option<T>, String>{
+    match read_frame(reader) {
+        Ok(Some(payload)) => match serde_json::from_slice(&payload) {
+            Ok(message) => Ok(Some(message)),
+            Err(e) => Err(format!("Malformed message: {}", e))
+        },
+        Ok(None) => Ok(None),
+        Err(e) => Err(format!("Couldn't read message: {}", e))
+    }
+}
+
+/// Exchanges handshakes with the peer, succeeding only if both speak the same protocol version
+pub fn handshake<S: Read + Write>(stream: &mut S) -> Result<(), String>{
+    write_message(stream, &Handshake::current())?;
+
+    match read_message::<S, Handshake>(stream)? {
+        Some(ref remote) if remote.version == PROTOCOL_VERSION => Ok(()),
+        Some(remote) => Err(format!("Protocol version mismatch: this build speaks version {} but the peer speaks version {}. Use matching builds of rlctl and rustland.", PROTOCOL_VERSION, remote.version)),
+        None => Err(String::from("The peer hung up during the handshake."))
+    }
+}
+
+mod test{
+    #[test]
+    fn frame_round_trip_test(){
+        use std::io::Cursor;
+        use super::{write_frame, read_frame, MAX_FRAME_LENGTH};
+
+        // Payloads containing the old '$' delimiter must survive intact
+        let payloads: Vec<&[u8]> = vec![b"{\"args\":[\"echo $HOME\"]}", b"", b"$$$"];
+
+        let mut buffer = Vec::<u8>::new();
+        for payload in payloads.iter() {
+            write_frame(&mut buffer, payload).unwrap();
+        }
+
+        let mut cursor = Cursor::new(buffer);
+        for payload in payloads.iter() {
+            assert_eq!(read_frame(&mut cursor).unwrap().unwrap().as_slice(), *payload);
+        }
+        assert!(read_frame(&mut cursor).unwrap().is_none());
+
+        // A header announcing more than the limit is refused
+        let oversized = (MAX_FRAME_LENGTH + 1) as u32;
+        let mut cursor = Cursor::new(vec![(oversized >> 24) as u8, (oversized >> 16) as u8, (oversized >> 8) as u8, oversized as u8]);
+        assert!(read_frame(&mut cursor).is_err());
+    }
+}
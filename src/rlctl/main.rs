@@ -1,8 +1,8 @@
 use std::env;
 use std::io::prelude::*;
 use std::net::TcpStream;
-use std::io::{Write, BufReader, BufWriter, BufRead};
-use std::iter::Peekable;
+use std::os::unix::net::UnixStream;
+use std::io::{Read, Write};
 use std::str;
 
 #[macro_use]
@@ -12,79 +12,103 @@ pub extern crate serde_json;
 
 extern crate common;
 use common::job::{Job, JobType};
-use common::definitions::{ElementReference, SOCKET_DETERMINANT, TAG_PREFIX};
+use common::event::Event;
+use common::definitions::{SOCKET_PORT, SOCKET_ENV_VAR};
+use common::command::job_from_args;
+use common::protocol::{handshake, read_message, write_message};
 
-fn parse_job_type(repr: String) -> Option<JobType>{
-    match repr.as_ref()
-    {
-        "focus" => Some(JobType::FOCUS),
-        "runapp" => Some(JobType::RUN_APP),
-        "tree" => Some(JobType::SEND_TREE),
-        "moveto" => Some(JobType::MOVE_TO),
-        _ => { 
-            println!("Unknown command.");  
-            return None;
-        }
-    }
-} 
-
-fn generate_job_from_args(mut args: Peekable<env::Args>) -> Option<Job>{
-    let mut job = Job::default();
+const TCP_FLAG: &str = "--tcp";
 
-    args.next();
+fn show_usage(){
+    println!("Usage: rlctl [--tcp] [PRIORITY_TAG] COMMAND [TAGS/ARGS]");
+    println!();
+    println!("Commands:");
+    println!(r#"    - tree: Sends back a list of elements in the window layout in a tree like format (or as JSON with --json).
+    - focus [left|right|up|down]: Focuses the referenced element, or the nearest window in a direction. 
+      Continues into the adjacent workspace if 'focus_wrapping' is enabled in the layout config.
+    - close: Asks the referenced window(s) to close.
+    - kill: Like close, but sends a signal to the processes of windows that are still open after a timeout 
+      (see the 'window' section of the config).
+    - fullscreen [on|off|toggle]: Lets the referenced (or focused) window cover the whole output.
+    - maximize [on|off|toggle]: Lets the referenced (or focused) window fill its workspace.
+    - float [on|off|toggle]: Moves the referenced (or focused) window between the tiling and the floating 
+      layer of its workspace. Floating windows are dragged with mod + left click.
+    - scratchpad [hide|show|toggle]: Hides the referenced (or focused) window in the scratchpad, or summons 
+      it as a floating window on the active workspace, e.g. 'rlctl @scratch_term scratchpad show'. 
+      Referencing @scratchpad itself addresses the first window hidden in it.
+    - rules test: Shows which of the [[rules]] in the config the referenced (or focused) window matches.
+    - runapp: Executes an application to start in the focused position of the layout.
+    - moveto: Moves an element in the layout to another place. 
+    - swap: Exchanges the places of two windows, e.g. 'rlctl swap @a @b'.
+    - resize grow|shrink left|right|up|down <amount>: Moves an edge of the referenced (or focused) window 
+      by an amount of pixels, e.g. 'rlctl @firefox resize grow left 100'.
+    - split horizontal|vertical|toggle|even|flatten: Sets the orientation of the split that the referenced 
+      (or focused) window is part of. 'even' gives the parts of the split equal shares, 'flatten' merges the 
+      nested splits of the same orientation into one split of many parts (e.g. three equal columns).
+    - tab next|prev|<index>|wrap|unwrap: Switches the tab of the tabbed container that the referenced (or 
+      focused) window is part of. 'wrap' turns the split the window is part of into tabs, 'unwrap' puts the 
+      tabs side by side again. Moving an element to a tabbed container adds it as a new tab.
+    - policy [auto_circulation|master_stack]: Arranges the referenced (or active) workspace by another layout 
+      policy, e.g. 'rlctl @mid_mid policy master_stack'. Without a name, the current policy is shown.
+    - master promote|count [+|-]<n>|ratio [+|-]<ratio>: Adjusts a workspace with the 'master_stack' policy. 
+      'promote' makes the referenced (or focused) window the first master (or lets it give way if it already 
+      is), 'count' sets the number of master windows and 'ratio' the share of the master area.
+    - layout save|load <name>|list: Saves the containers, ratios, tags and layout policies of the workspaces 
+      to ~/.config/rustland/layouts/<name>.json, or restores them. The windows present are put back in places 
+      of the same application where possible, the remaining places are left unoccupied for new windows.
+    - session save|restore: Saves the layout along with the commands that the windows were started with 
+      (through runapp or a hotkey) to ~/.config/rustland/session.json, or restores it and relaunches the 
+      applications into their places. With 'restore = true' under [session] in the configuration, this is 
+      done automatically on exit and startup.
+    - compact: Collapses the slivers that closed windows leave behind in the layout and removes elements no 
+      longer in use, so that their identities are given out again. This also happens as windows close.
+    - presel left|right|up|down [ratio]|cancel: Chooses the side of the referenced (or focused) window where 
+      the next window is placed, and the share of the space it gets (0.5 by default).
+    - subscribe: Keeps listening and prints events (as JSON) until interrupted. Optionally only the named 
+      events: view_created, view_destroyed, focus, workspace, workspaces, tag, config_reload.
+    - reload: Reloads the configuration file.
+    - workspace add|remove [row|column]: Adds a row/column of workspaces next to the active one, or removes 
+      the (empty) row/column that the referenced or active workspace is part of.
+    "#);
+    println!("Options:");
+    println!(r#"    --tcp: Connect through the TCP port instead of the session socket (${}).
+    "#, SOCKET_ENV_VAR);
+}
 
-    if let Some(head_tag_or_cmd) = args.next(){
-        if head_tag_or_cmd.contains(TAG_PREFIX){
-            job.main_ref = Some(ElementReference::Tag(head_tag_or_cmd.trim().replace(TAG_PREFIX, "")));
-        }
-        else{
-            match parse_job_type(head_tag_or_cmd){
-                Some(job_type) => job.job_type = job_type,
-                None => return None
-            }
-        }
+fn send_job<S: Read + Write>(mut stream: S, job: Job){
+    if let Err(e) = handshake(&mut stream){
+        println!("{}", e);
+        return;
     }
-    
-    if job.main_ref.is_some(){
-        if let Some(command) = args.next(){
-            println!("{}", command);
 
-            match parse_job_type(command){
-                Some(job_type) => job.job_type = job_type,
-                None => return None
-            }
-        }
-        else{
-            println!("No arguments given.");
-            return None;
-        }
+    if let Err(e) = write_message(&mut stream, &job){
+        println!("Error occured while writing to server: {}", e);
+        return;
     }
 
-    while let Some(arg) = args.next(){
-        if arg.contains(TAG_PREFIX){
-            if job.main_ref.is_none(){
-                job.main_ref = Some(ElementReference::Tag(arg.replace(TAG_PREFIX, "")));
-            }
-            else{
-                job.contextual_refs.push(ElementReference::Tag(arg.replace(TAG_PREFIX, "")));
+    if job.job_type == JobType::SUBSCRIBE {
+        // Events are printed line by line as they arrive
+        loop {
+            match read_message::<S, Event>(&mut stream){
+                Ok(Some(event)) => println!("{}", serde_json::to_string(&event).unwrap()),
+                Ok(None) => return,
+                Err(e) => { 
+                    println!("{}", e); 
+                    return; 
+                }
             }
         }
-        else{
-            job.anonymous_args.push(arg);
-        }
     }
 
-    return Some(job);
-}
-
-fn show_usage(){
-    println!("Usage: rlctl [PRIORITY_TAG] COMMAND [TAGS/ARGS]");
-    println!();
-    println!("Commands:");
-    println!(r#"    - tree: Sends back a list of elements in the window layout in a tree like format.
-    - runapp: Executes an application to start in the focused position of the layout.
-    - moveto: Moves an element in the layout to another place. 
-    "#);
+    match read_message::<S, Job>(&mut stream){
+        Ok(Some(job)) => match job.generated_result
+        {
+            Ok(result) => println!("{}", result),
+            Err(e) => println!("{}", e)
+        },
+        Ok(None) => println!("The compositor closed the connection without responding."),
+        Err(e) => println!("{}", e)
+    }
 }
 
 fn main(){
@@ -96,38 +120,28 @@ fn main(){
         }
     }
 
-    if let Ok(mut stream) = TcpStream::connect("localhost:4451"){
-        let mut reader = BufReader::new(&stream);
-        let mut writer = BufWriter::new(&stream);
+    let use_tcp = env::args().any(|arg| arg == TCP_FLAG);
+    let job = match job_from_args(env::args().skip(1).filter(|arg| arg != TCP_FLAG)){
+        Ok(job) => job,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
 
-        if let Some(job) = generate_job_from_args(env::args().peekable()){
-            writer.write_all(serde_json::to_string(&job).unwrap().as_bytes());
-            writer.write(&[SOCKET_DETERMINANT]);
-            
-            if let Err(e) = writer.flush(){
-                println!("Error occured while writing to server: {}", e)
-            }
-        
-            let mut input_buffer = Vec::<u8>::new();
-            reader.read_until(SOCKET_DETERMINANT, &mut input_buffer);
-            if input_buffer.pop() == None { return; }
-            
-            match str::from_utf8(input_buffer.as_slice()){
-                Ok(v) => {
-                    if let Ok(job) = serde_json::from_str::<Job>(v) {
-                        match job.generated_result
-                        {
-                            Ok(result) => println!("{}", result),
-                            Err(e) => println!("{}", e)
-                        }
-                    }
-                },
-                _ => {}
-            }
+    if use_tcp {
+        match TcpStream::connect(("localhost", SOCKET_PORT)){
+            Ok(stream) => send_job(stream, job),
+            Err(_) => println!("Could not connect to Rustland compositor/server. Are you sure it's running?")
         }
     }
-    else{
-        println!("Could not connect to Rustland compositor/server. Are you sure it's running?");
+    else {
+        match env::var(SOCKET_ENV_VAR){
+            Ok(socket_path) => match UnixStream::connect(&socket_path){
+                Ok(stream) => send_job(stream, job),
+                Err(_) => println!("Could not connect to Rustland compositor at {}. Are you sure it's running?", socket_path)
+            },
+            Err(_) => println!("${} is not set - is rlctl running inside a Rustland session? Use {} to connect over TCP.", SOCKET_ENV_VAR, TCP_FLAG)
+        }
     }
 }
-
@@ -4,12 +4,14 @@ use std::thread::{JoinHandle, spawn, sleep};
 use std::sync::atomic::AtomicBool;
 use std::sync::MutexGuard;
 
-use common::job::Job;
-use common::definitions::FPS;
+use common::job::{Job, JobType};
+use common::definitions::{FPS, COMPACTION_DELAY};
 use layout::LayoutTree;
-use io::process_all_current_jobs;
-use io::tcp_server::{TCP_SOCKET, handle_client};
-use wmstate::{WM_STATE, ACTIVE_TRANSITIONS, PENDING_JOBS, FINALIZED_JOBS};
+use io::{process_all_current_jobs, enqueue_job};
+use io::client::handle_client;
+use io::tcp_server::TCP_SOCKET;
+use io::unix_server::UNIX_SOCKET;
+use wmstate::{WM_STATE, ACTIVE_TRANSITIONS, PENDING_COMPACTION};
 
 use image;
 use image::RgbaImage;
@@ -40,6 +42,14 @@ pub fn schedule_job_routine(){
             // Continous processing of jobs
             sleep(time::Duration::from_millis(delta));
 
+            // The layout is compacted once windows have stopped closing for a while
+            if let Ok(mut pending_compaction) = PENDING_COMPACTION.lock() {
+                if pending_compaction.map_or(false, |since| since.elapsed() >= time::Duration::from_millis(COMPACTION_DELAY)) {
+                    *pending_compaction = None;
+                    enqueue_job(Job::init_unconditional(JobType::COMPACT));
+                }
+            }
+
             process_all_current_jobs();
         }
     });
@@ -81,7 +91,13 @@ pub fn schedule_tcp_routine(){
                 for stream in socket_instance.incoming() {
                     match stream {
                         Ok(mut stream) => {
-                            handle_client(stream);
+                            let client_name = match stream.peer_addr() {
+                                Ok(addr) => format!("{}", addr),
+                                Err(_) => String::from("tcp")
+                            };
+
+                            // Long-lived clients (e.g. subscribers) mustn't hold up the others
+                            spawn(move || handle_client(stream, client_name));
                         }
                         Err(e) => { /* connection failed */ }
                     }
@@ -89,4 +105,21 @@ pub fn schedule_tcp_routine(){
             }
         }
     });
-}
\ No newline at end of file
+}
+
+pub fn schedule_unix_routine(){
+    spawn(||{
+        if let Some(ref unix_socket) = *UNIX_SOCKET{
+            if let Ok(ref socket_instance) = unix_socket.try_lock(){
+                for stream in socket_instance.incoming() {
+                    match stream {
+                        Ok(stream) => {
+                            spawn(move || handle_client(stream, String::from("unix")));
+                        }
+                        Err(e) => { /* connection failed */ }
+                    }
+                }
+            }
+        }
+    });
+}
@@ -4,28 +4,39 @@ use std::process::Command;
 use std::io::Read;
 use std::thread::{spawn, sleep};
 use std::time;        
+use std::time::Instant;
 use std::ops::DerefMut;
+use std::env;
 use std::env::home_dir;
 use std::path::PathBuf;
 
 use common::definitions::{WM_FORWARD_EVENT_TO_CLIENT, WM_CATCH_EVENT};
 use common::job::{Job, JobType};
-use common::definitions::{FPS, ElementReference};
+use common::event::Event;
+use common::command::job_from_line;
+use common::definitions::{FPS, ElementReference, LayoutElemID, SOCKET_ENV_VAR};
 use config::Config;
+use config::rules;
 use io::physical::InputDevice;
+use io::enqueue_job;
+use io::event::broadcast;
+use io::unix_server::{bound_socket_path, remove_session_socket};
 use layout::element::{LayoutElement, LayoutElementProfile};
-use layout::element::window::Window;
+use layout::element::window::{Window, WindowMode};
 use layout::element::grid::Direction;
 use layout::element::bisect::Orientation;
-use layout::LayoutTree;
+use layout::{LayoutTree, PARENT_ELEMENT};
+use layout::arrangement;
 use layout::arrangement::tree;
+use layout::floating;
+use layout::snapshot::LayoutSnapshot;
 
 use utils::geometry::{PointExt, SizeExt, GeometryExt};
-use wmstate::{WMState, WM_STATE, PENDING_JOBS, FINALIZED_JOBS, ACTIVE_TRANSITIONS};
-use async::{schedule_wallpaper_init, schedule_job_routine, schedule_animator_routine, schedule_tcp_routine};
+use wmstate::{WMState, WM_STATE, ACTIVE_TRANSITIONS, PENDING_COMPACTION};
+use async::{schedule_wallpaper_init, schedule_job_routine, schedule_animator_routine, schedule_tcp_routine, schedule_unix_routine};
 use sugars::solid_color::SolidColor;
 
-use wlc::{Callback, Key, KeyState, Point, Size, Geometry, ButtonState, View, Output, Visibility, Modifier, Modifiers, Button, terminate};
+use wlc::{Callback, Key, KeyState, Point, Size, Geometry, ButtonState, View, Output, Visibility, Modifier, Modifiers, Button, ViewState, terminate};
 use wlc::render::{RenderOutput, RenderView};
 use wlc::TouchType;
 use wlc::ResizeEdge::Flags;
@@ -47,9 +58,20 @@ impl Callback for Compositor {
             }
 
             // Initialize layout tree:
-            wm_state.tree = wm_state.config.layout.construct_tree();
+            wm_state.tree = match wm_state.config.layout.construct_tree() {
+                Ok(tree) => tree,
+                // There is nothing to arrange the windows in without it
+                Err(msg) => panic!("Couldn't construct the layout: {}", msg)
+            };
             LayoutTree::refresh(&mut wm_state);
 
+            // Relaunch the applications of the last session:
+            if wm_state.config.session.restore && LayoutSnapshot::session_path().exists() {
+                match LayoutSnapshot::load_from_file(LayoutSnapshot::session_path()).and_then(|snapshot| snapshot.restore(&mut wm_state, true)) {
+                    Ok(msg) | Err(msg) => println!("{}", msg)
+                }
+            }
+
             // Initialize input device:
             wm_state.input_dev = Some(InputDevice::init());
             
@@ -57,10 +79,13 @@ impl Callback for Compositor {
             let program = wm_state.init_graphics_program(); 
             wm_state.render_background();
             let grid_tag = wm_state.config.layout.grid_tag.clone();
-            for mut sub in wm_state.tree.lookup_element_by_tag("sub".to_string())  {
-                if let LayoutElementProfile::Padding(ref mut padding) = sub.profile {
-                    //padding.apply_frame(wm_state.graphics_program.as_ref().unwrap(), 1.0f32);
-                }
+            match wm_state.tree.lookup_element_by_tag("sub".to_string()) {
+                Ok(subs) => for mut sub in subs {
+                    if let LayoutElementProfile::Padding(ref mut padding) = sub.profile {
+                        //padding.apply_frame(wm_state.graphics_program.as_ref().unwrap(), 1.0f32);
+                    }
+                },
+                Err(msg) => println!("{}", msg)
             }
 
             if let Some(wallpaper_path) = wm_state.config.background.wallpaper_path.clone(){
@@ -72,11 +97,27 @@ impl Callback for Compositor {
             }
         }
 
+        // Children (e.g. rlctl started from a terminal) find the session socket through this:
+        if let Some(socket_path) = bound_socket_path(){
+            env::set_var(SOCKET_ENV_VAR, socket_path);
+        }
+
         schedule_job_routine();              
         schedule_tcp_routine();    
+        schedule_unix_routine();
         schedule_animator_routine();    
     }
 
+    fn compositor_terminate(&mut self){
+        // However the compositor goes down, the session is kept - going down by mod+Esc it was saved while the state was still held
+        if let Ok(wm_state) = WM_STATE.try_read() {
+            save_session(&wm_state);
+        }
+
+        // ...and the socket shouldn't be left behind
+        remove_session_socket();
+    }
+
     fn output_render_pre(&mut self, output: &mut RenderOutput) {        
         if let Ok(mut wm_state) = WM_STATE.write(){
             let image_loaded = match wm_state.next_wallpaper_image {
@@ -106,28 +147,22 @@ impl Callback for Compositor {
             view.focus();    
 
             if view.view_type().is_empty(){
-                let mut window = Window::init_dummy();
-                window.attach_view(view.weak_reference());
-                
-                if view.view_type().is_empty(){
-                    let mut layout_policy = wm_state.tree.layout_policy.clone();
-                    let window_elem_id = layout_policy.seat_window(&mut wm_state.tree); 
-                    
-                    layout_policy.decorate_window(&mut wm_state, window_elem_id);
-                    
-                    wm_state.tree.layout_policy = layout_policy;
-
-                    wm_state.tree.reserve_element_identity(window_elem_id, LayoutElementProfile::Window(window));  
-                    
-                    let tag = format!("{}", window_elem_id);
-
-                    if !tag.is_empty(){
-                        wm_state.tree.tags.tag_element(tag.as_ref(), window_elem_id);
+                match seat_view(&mut wm_state, view) {
+                    Ok(window_elem_id) => {
+                        broadcast(Event::ViewCreated { 
+                            element_id: window_elem_id, 
+                            pid: view.pid(), 
+                            title: view.title().to_string(), 
+                            app_id: view.app_id().to_string() 
+                        });
+
+                        enqueue_job(Job::init_unconditional(JobType::LAYOUT_REFRESH));
+                    },
+                    Err(msg) => {
+                        // Without a place in the layout the view is refused
+                        println!("Couldn't seat '{}': {}", view.app_id(), msg);
+                        return false;
                     }
-
-                    if let Ok(mut pending_jobs) = PENDING_JOBS.try_lock(){
-                        pending_jobs.push(Job::init_unconditional(JobType::LAYOUT_REFRESH));
-                    } 
                 }
             }
         }
@@ -137,19 +172,34 @@ impl Callback for Compositor {
     
     fn view_destroyed(&mut self, view: &View){
         if let Ok(mut wm_state) = WM_STATE.write() {
-            let mut layout_policy = wm_state.tree.layout_policy.clone();
             if let Some(element_ident) = wm_state.tree.lookup_element_from_view(view){
-                layout_policy.detach_window(&mut wm_state.tree, element_ident);
+                if let Err(msg) = unseat_window(&mut wm_state.tree, element_ident) {
+                    println!("Couldn't detach [{}]: {}", element_ident, msg);
+                }
                 wm_state.tree.remove_view_binding_to(element_ident);
+
+                broadcast(Event::ViewDestroyed { element_id: element_ident, pid: view.pid() });
+
+                // What the window leaves behind is cleaned up a while after the last window closed
+                if let Ok(mut pending_compaction) = PENDING_COMPACTION.lock() {
+                    *pending_compaction = Some(Instant::now());
+                }
             }
         }
     }
 
     fn view_focus(&mut self, view: &View, focused: bool) {
         if focused && view.view_type().is_empty(){
-            if let Ok(mut pending_jobs) = PENDING_JOBS.try_lock(){
-                pending_jobs.push(Job::init(JobType::FOCUS, Some(ElementReference::ViewPID(view.pid())), Vec::new()));
-            }  
+            enqueue_job(Job::init(JobType::FOCUS, Some(ElementReference::ViewPID(view.pid())), Vec::new()));
+        }
+    }
+
+    fn view_request_state(&mut self, view: &View, state: ViewState::Flags, toggle: bool) {
+        if state.contains(ViewState::Fullscreen) && view.view_type().is_empty() {
+            let mut job = Job::init(JobType::FULLSCREEN, Some(ElementReference::ViewPID(view.pid())), Vec::new());
+            job.anonymous_args.push(String::from(if toggle { "on" } else { "off" }));
+
+            enqueue_job(job);
         }
     }
 
@@ -173,95 +223,112 @@ impl Callback for Compositor {
 
                 //Press F5 to force an update to the arrangement
                 if sym == Key::F4{
-                    if let Ok(mut pending_jobs) = PENDING_JOBS.try_lock(){
-                        pending_jobs.push(Job::init_unconditional(JobType::LAYOUT_REFRESH));
-                    } 
+                    enqueue_job(Job::init_unconditional(JobType::LAYOUT_REFRESH));
                     return WM_CATCH_EVENT;
                 }
+
                 if wm_state.config.keyboard.mod_key_is_pressed(modifiers.mods) {
                     // Window manager catch modifier
                     if let Some(&root_ident) = wm_state.tree.tags.address_element_by_tag(wm_state.config.layout.root_tag.clone()).first() {
-                        if sym == meta_view_key {
-                            if let Some(&jumper_ident) = wm_state.tree.tags.address_element_by_tag(wm_state.config.layout.jumper_tag.clone()).first() {
-                                wm_state.tree.animate_property(jumper_ident, "offset_x", 0f32, false, 250);
-                                wm_state.tree.animate_property(jumper_ident, "offset_y", 0f32, false, 250);
-                            }
-                            wm_state.tree.animate_property(root_ident, "inner_scale_x", 1f32 / wm_state.config.layout.grid_width() as f32, false, 125);
-                            wm_state.tree.animate_property(root_ident, "inner_scale_y", 1f32 / wm_state.config.layout.grid_height() as f32, false, 125);
+                        let animated = if sym == meta_view_key {
+                            let jumper_animated = match wm_state.tree.tags.address_element_by_tag(wm_state.config.layout.jumper_tag.clone()).first() {
+                                Some(&jumper_ident) => wm_state.tree.animate_property(jumper_ident, "offset_x", 0f32, false, 250)
+                                    .and_then(|_| wm_state.tree.animate_property(jumper_ident, "offset_y", 0f32, false, 250)),
+                                None => Ok(())
+                            };
+                            let (grid_w, grid_h) = match wm_state.tree.grid_dimensions() {
+                                Ok(Some(dimensions)) => dimensions,
+                                _ => (wm_state.config.layout.grid_width(), wm_state.config.layout.grid_height())
+                            };
+
+                            jumper_animated
+                                .and_then(|_| wm_state.tree.animate_property(root_ident, "inner_scale_x", 1f32 / grid_w as f32, false, 125))
+                                .and_then(|_| wm_state.tree.animate_property(root_ident, "inner_scale_y", 1f32 / grid_h as f32, false, 125))
                         }
                         else {
-                            wm_state.tree.animate_property(root_ident, "inner_scale_x", 1.0f32, false, 125);
-                            wm_state.tree.animate_property(root_ident, "inner_scale_y", 1.0f32, false, 125);
+                            wm_state.tree.animate_property(root_ident, "inner_scale_x", 1.0f32, false, 125)
+                                .and_then(|_| wm_state.tree.animate_property(root_ident, "inner_scale_y", 1.0f32, false, 125))
+                        };
+
+                        if let Err(msg) = animated {
+                            println!("{}", msg);
                         }
                     }
+                }
+
+                // Key sequences bound to commands in the config
+                if let Some(command) = wm_state.config.keyboard.matching_command(modifiers.mods, sym) {
+                    match job_from_line(&command) {
+                        Ok(job) => enqueue_job(job),
+                        Err(msg) => println!("Couldn't run the bound command '{}': {}", command, msg)
+                    }
+
+                    return WM_CATCH_EVENT;
+                }
+
+                if wm_state.config.keyboard.mod_key_is_pressed(modifiers.mods) {
 
-                    let display_geometry = wm_state.tree.get_outer_geometry();
-                    let mut new_workspace_offset = None;
                     if sym == Key::Left || sym == Key::Right || sym == Key::Up || sym == Key::Down {
 
                         let mut pre = None;
                         let mut post = None;
                         let animation_time = 500;
 
-                        if let Some(mut element) = wm_state.tree.lookup_element_by_tag(wm_state.config.layout.grid_tag.clone()).first_mut() {
-                            match element.profile{
-                                LayoutElementProfile::Grid(ref mut grid) => {
-                                    pre = Some(grid.get_active_child_id());
+                        match wm_state.tree.lookup_element_by_tag(wm_state.config.layout.grid_tag.clone()) {
+                            Ok(mut grids) => if let Some(element) = grids.first_mut() {
+                                match element.profile{
+                                    LayoutElementProfile::Grid(ref mut grid) => {
+                                        pre = Some(grid.get_active_child_id());
                                     
-                                    grid.switch_to_subspace_in_direction(
-                                        match sym{
-                                            Key::Left => Direction::LEFT,
-                                            Key::Right => Direction::RIGHT,
-                                            Key::Up => Direction::UP,
-                                            Key::Down => Direction::DOWN,
-                                            _ => panic!("The number of key check are more than the possible direction.")
+                                        grid.switch_to_subspace_in_direction(
+                                            match sym{
+                                                Key::Left => Direction::LEFT,
+                                                Key::Right => Direction::RIGHT,
+                                                Key::Up => Direction::UP,
+                                                Key::Down => Direction::DOWN,
+                                                _ => panic!("The number of key check are more than the possible direction.")
+                                            }
+                                        );
+
+                                        post = Some(grid.get_active_child_id());
+                                        if pre != post {
+                                            broadcast(Event::Workspace { index: grid.active_subspace(), element_id: post.unwrap() });
                                         }
-                                    );
 
-                                    post = Some(grid.get_active_child_id());
-                                    wm_state.tree.animate_property_explicitly(post.unwrap(), "frame_opacity", 0.0f32, 1.0f32, false, animation_time, 0);
-                                    new_workspace_offset = Some(grid.get_offset_geometry(display_geometry, Geometry::zero(), grid.active_subspace() as u16, &mut (1.0f32, 1.0f32)));
+                                        wm_state.tree.animate_property_explicitly(post.unwrap(), "frame_opacity", 0.0f32, 1.0f32, false, animation_time, 0);
+                                    }
+                                    _ => { panic!("Expected element to be a workspace.") }
                                 }
-                                _ => { panic!("Expected element to be a workspace.") }
-                            }
+                            },
+                            Err(msg) => println!("{}", msg)
                         }
                         
-                        if let Some(&jumper_ident) = wm_state.tree.tags.address_element_by_tag(wm_state.config.layout.jumper_tag.clone()).first() {
-                            if let Some(geometry) = new_workspace_offset{
-                                wm_state.tree.animate_property(jumper_ident, "offset_x", -geometry.origin.x as f32, false, 300);
-                                wm_state.tree.animate_property(jumper_ident, "offset_y", -geometry.origin.y as f32, false, 300);
-
-                                let zoom_magnitude = (wm_state.tree.get_outer_geometry().size.w / 10) as f32;
-                                //wm_state.tree.animate_property(jumper_ident, "gap_size", zoom_magnitude, false, 125);
-                                //wm_state.tree.animate_property_explicitly(jumper_ident, "gap_size", zoom_magnitude, 0f32 as f32, false, 125, 126);
-                            }
-                        }
-                        if let Ok(mut pending_jobs) = PENDING_JOBS.try_lock(){
-                            pending_jobs.push(Job::init_unconditional(JobType::LAYOUT_REFRESH));
-                        } 
+                        wm_state.jump_to_active_workspace(300);
+                        enqueue_job(Job::init_unconditional(JobType::LAYOUT_REFRESH));
 
                         return WM_CATCH_EVENT;
                     }
 
                     if let Some(v) = view  {
                         if sym == Key::C {
-                            v.close();
+                            enqueue_job(Job::init(JobType::CLOSE, Some(ElementReference::ViewPID(v.pid())), Vec::new()));
                             
                             return WM_CATCH_EVENT;
                         } 
                     }
 
                     if sym == Key::Esc {
+                        save_session(&wm_state);
+
                         terminate();  
                         return WM_CATCH_EVENT;
                     }
                 }
 
                 if let Some(matching_hotkey_executable) = wm_state.config.keyboard.matching_hotkey(modifiers.mods, sym) {
-                    Command::new("sh")
-                        .arg("-c")
-                        .arg(matching_hotkey_executable)
-                        .spawn();
+                    if let Err(msg) = wm_state.launch(matching_hotkey_executable) {
+                        println!("{}", msg);
+                    }
 
                     return WM_CATCH_EVENT;
                 }
@@ -273,6 +340,21 @@ impl Callback for Compositor {
 
     fn pointer_button(&mut self, view: Option<&View>, _time: u32, modifiers: Modifiers, button: Button, state: ButtonState, origin: Point) -> bool {
         if let Ok(mut wm_state) = WM_STATE.write() {
+            // Floating windows are dragged around with mod + left click
+            let drag = match view {
+                Some(v) if button == Button::Left && wm_state.config.keyboard.mod_key_is_pressed(modifiers.mods) => {
+                    wm_state.tree.lookup_element_from_view(v).and_then(|elem_ident| match floating::is_floating(&wm_state.tree, elem_ident) {
+                        Ok(true) => Some(elem_ident),
+                        Ok(false) => None,
+                        Err(msg) => {
+                            println!("Couldn't drag [{}]: {}", elem_ident, msg);
+                            None
+                        }
+                    })
+                },
+                _ => None
+            };
+
             if let Some(ref mut input_dev) = wm_state.input_dev{
                 input_dev.left_click = ButtonState::Released;
                 input_dev.right_click = ButtonState::Released;
@@ -287,6 +369,11 @@ impl Callback for Compositor {
                     if let Some(v) = view {
                         v.focus();
                         
+                        if drag.is_some() {
+                            input_dev.drag = drag;
+                            return WM_CATCH_EVENT;
+                        }
+
                         if modifiers.mods.contains(Modifier::Ctrl) {
                             return WM_CATCH_EVENT;
                         }
@@ -294,6 +381,7 @@ impl Callback for Compositor {
                 }
                 else {
                     input_dev.resize = None;
+                    input_dev.drag = None;
                 } 
             }
         }
@@ -320,28 +408,41 @@ impl Callback for Compositor {
 
                 if let Some( (element_ident, orientation) ) = dev.resize {
                     // Handle window resizing
-                    let (x, y, w, h) = {
-                        let parent = tree.parent_of(element_ident);
-                        let parent_geometry = tree.geometry_of(parent).unwrap();
-
-                        (
-                            parent_geometry.origin.x as f32, 
-                            parent_geometry.origin.y as f32,
-                            parent_geometry.size.w as f32, 
-                            parent_geometry.size.h as f32
-                        )
-                    };
-
-                    tree.animate_property (
-                        element_ident, 
-                        "ratio", 
-                        match orientation { 
-                            Orientation::Horizontal => (point.x as f32 - x) / w, 
-                            Orientation::Vertical => (point.y as f32 - y) / h
+                    match tree.parent_of(element_ident).and_then(|parent| tree.geometry_of(parent)) {
+                        Ok(Some(parent_geometry)) => {
+                            let (x, y, w, h) = (
+                                parent_geometry.origin.x as f32, 
+                                parent_geometry.origin.y as f32,
+                                parent_geometry.size.w as f32, 
+                                parent_geometry.size.h as f32
+                            );
+
+                            let resized = tree.animate_property (
+                                element_ident, 
+                                "ratio", 
+                                match orientation { 
+                                    Orientation::Horizontal => (point.x as f32 - x) / w, 
+                                    Orientation::Vertical => (point.y as f32 - y) / h
+                                },
+                                false, 
+                                1
+                            );
+
+                            if let Err(msg) = resized {
+                                println!("Couldn't resize: {}", msg);
+                            }
                         },
-                        false, 
-                        1
-                    );
+                        Ok(None) => {},
+                        Err(msg) => println!("Couldn't resize: {}", msg)
+                    }
+                }
+
+                if let Some(element_ident) = dev.drag {
+                    // Handle floating window dragging
+                    match floating::move_floating(tree, element_ident, dx, dy) {
+                        Ok(_) => enqueue_job(Job::init_unconditional(JobType::LAYOUT_REFRESH)),
+                        Err(msg) => println!("Couldn't drag: {}", msg)
+                    }
                 }
 
                 active_right_click = dev.right_click == ButtonState::Pressed;
@@ -357,14 +458,180 @@ impl Callback for Compositor {
             let &mut WMState {ref tree, ref mut input_dev, ..} = wm_state.deref_mut();
             
             if let Some(elem_ident) = tree.lookup_element_from_view(view) {
-                let parent_id = tree.parent_of(elem_ident);
-
-                input_dev.as_mut().unwrap().resize = match tree.lookup_element(parent_id).unwrap().profile {
-                    LayoutElementProfile::Bisect(ref bisect) => Some( (parent_id, bisect.orientation) ),
-                    _ => None
+                let resize = match tree.parent_of(elem_ident) {
+                    Ok(parent_id) => match tree.try_lookup_element(parent_id) {
+                        Ok(Some(parent)) => match parent.profile {
+                            LayoutElementProfile::Bisect(ref bisect) => Some( (parent_id, bisect.orientation) ),
+                            _ => None
+                        },
+                        Ok(None) => None,
+                        Err(msg) => {
+                            println!("Couldn't resize: {}", msg);
+                            None
+                        }
+                    },
+                    Err(msg) => {
+                        println!("Couldn't resize: {}", msg);
+                        None
+                    }
                 };
+
+                input_dev.as_mut().unwrap().resize = resize;
             }
             
         }
     }
 }
+
+/// Saves the arrangement of the session, so that the next one can restore it (if enabled in the config)
+fn save_session(wm_state: &WMState) {
+    if wm_state.config.session.restore {
+        if let Err(msg) = LayoutSnapshot::capture(wm_state).and_then(|snapshot| snapshot.save_to_file(LayoutSnapshot::session_path())) {
+            println!("Couldn't save the session: {}", msg);
+        }
+    }
+}
+
+/// Finds a place in the layout for a new view - a window element is made for it in that place
+fn seat_view(wm_state: &mut WMState, view: &View) -> Result<LayoutElemID, String> {
+    let mut window = Window::init_dummy();
+    window.attach_view(view.weak_reference());
+
+    let launching_command = wm_state.launched_commands.get(&view.pid()).cloned();
+    let outcome = rules::resolve(&wm_state.config.rules, view.app_id(), view.title(), launching_command.as_ref().map(|command| command.as_str()));
+
+    if !outcome.matched.is_empty() {
+        println!("Rules applied to '{}': {}", view.app_id(), outcome.matched.join(", "));
+    }
+
+    let workspace_ident = match outcome.workspace {
+        Some(ref workspace_tag) => wm_state.tree.tags.address_element_by_tag(workspace_tag.clone()).first().cloned(),
+        None => None
+    };
+
+    // An application relaunched by a restored session goes back to its reserved place (if still unoccupied) -
+    // found by its pid, or by its app_id if the pid was the one of the shell that launched it
+    let seat_index = {
+        let seats = &wm_state.tree.session_seats;
+
+        seats.iter().position(|seat| seat.pid == view.pid())
+            .or(seats.iter().position(|seat| seat.app_id.as_ref().map_or(false, |app_id| app_id == view.app_id())))
+    };
+    let session_seat = match seat_index.map(|index| wm_state.tree.session_seats.remove(index).slot_ident) {
+        Some(slot_ident) => match wm_state.tree.try_lookup_element(slot_ident)? {
+            Some(ref slot) if slot.profile.is_none() => Some(slot_ident),
+            _ => None
+        },
+        None => None
+    };
+
+    // A window that a rule assigns to a slot of a workspace template drops into it (if still unoccupied)
+    let mut template_seat = None;
+    if let Some(ref slot_tag) = outcome.slot {
+        for slot_ident in wm_state.tree.tags.address_element_by_tag(slot_tag.clone()) {
+            let unoccupied = match wm_state.tree.try_lookup_element(slot_ident)? {
+                Some(slot) => slot.profile.is_none(),
+                None => false
+            };
+
+            if unoccupied {
+                template_seat = Some(slot_ident);
+                break;
+            }
+        }
+    }
+
+    let reserved_seat = session_seat.or(template_seat);
+
+    let reserved_workspace = match reserved_seat {
+        Some(slot_ident) => arrangement::workspace_of(&wm_state.tree, slot_ident)?,
+        None => None
+    };
+    let active_workspace = wm_state.tree.active_workspace()?.map(|(_, ident)| ident);
+    let workspace_ident = reserved_workspace.or(workspace_ident).or(active_workspace).unwrap_or(PARENT_ELEMENT);
+    let mut layout_policy = wm_state.tree.policy_of(workspace_ident);
+
+    // Dialogs float above the windows they belong to (unless a rule says otherwise)
+    let floating_seat = match reserved_seat.is_none() && outcome.floating.unwrap_or(view.parent().is_some()) {
+        true => match floating::seat_floating(&mut wm_state.tree, workspace_ident, view.geometry()) {
+            Ok(floating_seat) => floating_seat,
+            Err(msg) => {
+                println!("Couldn't float the window: {}", msg);
+                None
+            }
+        },
+        false => None
+    };
+
+    let window_elem_id = match (reserved_seat, floating_seat) {
+        (Some(slot_ident), _) => slot_ident,
+        (None, Some((floating_ident, relative_geometry))) => {
+            window.floating = Some(relative_geometry);
+            floating_ident
+        },
+        (None, None) => match outcome.workspace {
+            // A preselection only concerns windows that would end up next to it anyway
+            Some(_) => layout_policy.seat_window(&mut wm_state.tree, workspace_ident)?,
+            None => match arrangement::seat_preselected(&mut wm_state.tree)? {
+                Some(preselected_ident) => preselected_ident,
+                None => layout_policy.seat_window(&mut wm_state.tree, workspace_ident)?
+            }
+        }
+    };
+
+    window.set_inner_offset(outcome.gap_size);
+
+    wm_state.tree.reserve_element_identity(window_elem_id, LayoutElementProfile::Window(window))?;
+
+    layout_policy.decorate_window(wm_state, window_elem_id)?;
+    
+    wm_state.tree.set_policy_of(workspace_ident, layout_policy);
+    
+    let tag = format!("{}", window_elem_id);
+
+    if !tag.is_empty(){
+        wm_state.tree.tags.tag_element(tag.as_ref(), window_elem_id);
+    }
+
+    for tag in outcome.tags.iter() {
+        wm_state.tree.tags.tag_element(tag, window_elem_id);
+    }
+
+    if let Some(frame_opacity) = outcome.frame_opacity {
+        wm_state.tree.animate_property_after_delay(window_elem_id, "frame_opacity", frame_opacity, false, 500, 200)?;
+    }
+
+    if outcome.fullscreen == Some(true) {
+        if let Err(msg) = arrangement::set_window_mode(wm_state, window_elem_id, WindowMode::Fullscreen, false) {
+            println!("{}", msg);
+        }
+    }
+
+    Ok(window_elem_id)
+}
+
+/// Takes the window of a closed view out of the layout
+fn unseat_window(tree: &mut LayoutTree, element_ident: LayoutElemID) -> Result<(), String> {
+    if floating::is_floating(tree, element_ident)? {
+        // Nothing in the tiling needs to adjust
+        if arrangement::detach_element(tree, element_ident).is_ok() {
+            tree.discard_element(element_ident)?;
+        }
+    }
+    else {
+        match arrangement::workspace_of(tree, element_ident)? {
+            Some(workspace_ident) => {
+                let mut layout_policy = tree.policy_of(workspace_ident);
+                layout_policy.detach_window(tree, element_ident)?;
+                tree.set_policy_of(workspace_ident, layout_policy);
+            },
+            None => {
+                // Not part of any workspace
+                let mut layout_policy = tree.layout_policy.clone();
+                layout_policy.detach_window(tree, element_ident)?;
+            }
+        }
+    }
+
+    Ok(())
+}
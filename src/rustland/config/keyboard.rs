@@ -18,6 +18,43 @@ pub struct KeyboardConfig {
     pub mod_key: String,
     pub meta_view_key: Key,
     pub hotkeys: HashMap<String, String>,
+
+    // Key sequences bound to compositor commands (in the syntax of rlctl) instead of executables
+    #[serde(default = "default_commands")]
+    pub commands: HashMap<String, String>
+}
+
+fn default_commands() -> HashMap<String, String> {
+    [
+        (format!("mod{}Shift{}Left",  KEY_DIVISOR, KEY_DIVISOR), "focus left".to_string()),
+        (format!("mod{}Shift{}Right", KEY_DIVISOR, KEY_DIVISOR), "focus right".to_string()),
+        (format!("mod{}Shift{}Up",    KEY_DIVISOR, KEY_DIVISOR), "focus up".to_string()),
+        (format!("mod{}Shift{}Down",  KEY_DIVISOR, KEY_DIVISOR), "focus down".to_string()),
+        (format!("mod{}Ctrl{}Left",   KEY_DIVISOR, KEY_DIVISOR), "resize grow left 50".to_string()),
+        (format!("mod{}Ctrl{}Right",  KEY_DIVISOR, KEY_DIVISOR), "resize grow right 50".to_string()),
+        (format!("mod{}Ctrl{}Up",     KEY_DIVISOR, KEY_DIVISOR), "resize grow up 50".to_string()),
+        (format!("mod{}Ctrl{}Down",   KEY_DIVISOR, KEY_DIVISOR), "resize grow down 50".to_string()),
+        (format!("mod{}Alt{}Left",    KEY_DIVISOR, KEY_DIVISOR), "resize shrink left 50".to_string()),
+        (format!("mod{}Alt{}Right",   KEY_DIVISOR, KEY_DIVISOR), "resize shrink right 50".to_string()),
+        (format!("mod{}Alt{}Up",      KEY_DIVISOR, KEY_DIVISOR), "resize shrink up 50".to_string()),
+        (format!("mod{}Alt{}Down",    KEY_DIVISOR, KEY_DIVISOR), "resize shrink down 50".to_string()),
+        (format!("mod{}Ctrl{}Shift{}Left",  KEY_DIVISOR, KEY_DIVISOR, KEY_DIVISOR), "presel left".to_string()),
+        (format!("mod{}Ctrl{}Shift{}Right", KEY_DIVISOR, KEY_DIVISOR, KEY_DIVISOR), "presel right".to_string()),
+        (format!("mod{}Ctrl{}Shift{}Up",    KEY_DIVISOR, KEY_DIVISOR, KEY_DIVISOR), "presel up".to_string()),
+        (format!("mod{}Ctrl{}Shift{}Down",  KEY_DIVISOR, KEY_DIVISOR, KEY_DIVISOR), "presel down".to_string()),
+        (format!("mod{}Ctrl{}Shift{}Esc",    KEY_DIVISOR, KEY_DIVISOR, KEY_DIVISOR), "presel cancel".to_string()),
+        (format!("mod{}S", KEY_DIVISOR), "split toggle".to_string()),
+        (format!("mod{}F", KEY_DIVISOR), "fullscreen toggle".to_string()),
+        (format!("mod{}M", KEY_DIVISOR), "maximize toggle".to_string()),
+        (format!("mod{}G", KEY_DIVISOR), "float toggle".to_string()),
+        (format!("mod{}Enter", KEY_DIVISOR), "master promote".to_string()),
+        (format!("mod{}W", KEY_DIVISOR), "tab wrap".to_string()),
+        (format!("mod{}Shift{}W", KEY_DIVISOR, KEY_DIVISOR), "tab unwrap".to_string()),
+        (format!("mod{}Period", KEY_DIVISOR), "tab next".to_string()),
+        (format!("mod{}Comma", KEY_DIVISOR), "tab prev".to_string()),
+        (format!("mod{}Minus", KEY_DIVISOR), "scratchpad hide".to_string()),
+        (format!("mod{}Shift{}Minus", KEY_DIVISOR, KEY_DIVISOR), "@scratchpad scratchpad toggle".to_string()),
+    ].iter().cloned().collect()
 }
 
 impl Default for KeyboardConfig {
@@ -32,24 +69,38 @@ impl Default for KeyboardConfig {
                 (format!("VolumeDown"),                 "pactl set-sink-volume 0 -5%".to_string()),
                 (format!("BrightnessUp"),               "gksu brightnessctl s 50+".to_string()),
                 (format!("BrightnessDown"),             "gksu brightnessctl s 50-".to_string()),
-            ].iter().cloned().collect()
+            ].iter().cloned().collect(),
+            commands: default_commands()
         }
     }
 }
 
 impl KeyboardConfig {
+    /// Adds the default commands that the user's map doesn't bind a key sequence of - so that configs written earlier get the newer ones
+    pub fn merge_default_commands(&mut self) {
+        for (sequence, command) in default_commands() {
+            self.commands.entry(sequence).or_insert(command);
+        }
+    }
+
     pub fn mod_key_is_pressed(&self, mods: Flags) -> bool {
         KeySequence::new(self.mod_key.clone()).matches(mods, None)
     }
 
     pub fn matching_hotkey(&self, mods: Flags, key: Key) -> Option<String>{        
-        for (str_seq, bin) in &self.hotkeys {
-            if KeySequence::new(str_seq.replace("mod", &self.mod_key)).matches(mods, Some(key)) {    
-                return Some(bin.clone());
-            }
-        }
+        self.matching_binding(&self.hotkeys, mods, key)
+    }
+
+    pub fn matching_command(&self, mods: Flags, key: Key) -> Option<String>{        
+        self.matching_binding(&self.commands, mods, key)
+    }
 
-        return None;
+    fn matching_binding(&self, bindings: &HashMap<String, String>, mods: Flags, key: Key) -> Option<String>{
+        // A sequence also matches when more modifiers are held than it names - so the most specific one wins
+        bindings.iter()
+            .filter(|&(str_seq, _)| KeySequence::new(str_seq.replace("mod", &self.mod_key)).matches(mods, Some(key)))
+            .max_by_key(|&(str_seq, _)| str_seq.matches(KEY_DIVISOR).count())
+            .map(|(_, bound)| bound.clone())
     }
 }
 
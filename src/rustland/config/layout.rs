@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use serde::ser::Serialize;
 use serde::de::Deserialize;
 use wlc::input::keyboard::Key;
@@ -9,6 +11,10 @@ use layout::element::{LayoutElement, LayoutElementProfile};
 use layout::element::padding::Padding;
 use layout::element::grid::Grid;
 use layout::LayoutTree;
+use layout::policy::LayoutPolicy;
+use layout::policy::auto_circulation::AutoCirculation;
+use layout::policy::master_stack::MasterStack;
+use config::template::TemplateNode;
 use utils::geometry::{PointExt, SizeExt, GeometryExt};
 
 #[derive(Serialize, Deserialize)]
@@ -17,9 +23,53 @@ pub struct LayoutConfig {
     pub focused_tag: String,
     pub jumper_tag: String,
     pub grid_tag: String,
+
+    // the holding area of hidden windows, outside of the grid
+    #[serde(default = "default_scratchpad_tag")]
+    pub scratchpad_tag: String,
+
     workspace_columns: usize,
     workspaces: Vec<String>,
-    monitor_resolution: Size
+    monitor_resolution: Size,
+
+    // Whether directional focus continues into the adjacent workspace when there is no window in that direction
+    #[serde(default)]
+    pub focus_wrapping: bool,
+
+    // How windows are placed: 'auto_circulation' or 'master_stack'
+    #[serde(default = "default_policy")]
+    pub policy: String,
+
+    // The number of windows in the master area and its share of a workspace (master_stack)
+    #[serde(default = "default_master_count")]
+    pub master_count: usize,
+    #[serde(default = "default_master_ratio")]
+    pub master_ratio: f32,
+
+    // Policies of individual workspaces (by name), overriding 'policy'
+    #[serde(default)]
+    pub workspace_policies: HashMap<String, String>,
+
+    // What workspaces (by name) start out with, e.g. named slots for the windows of certain applications
+    #[serde(default)]
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub templates: HashMap<String, TemplateNode>
+}
+
+fn default_scratchpad_tag() -> String {
+    "scratchpad".to_string()
+}
+
+fn default_policy() -> String {
+    "auto_circulation".to_string()
+}
+
+fn default_master_count() -> usize {
+    1usize
+}
+
+fn default_master_ratio() -> f32 {
+    0.55f32
 }
 
 impl Default for LayoutConfig {
@@ -29,13 +79,20 @@ impl Default for LayoutConfig {
             focused_tag: "focused".to_string(),
             jumper_tag: "jumper".to_string(),
             grid_tag: "grid".to_string(),
+            scratchpad_tag: default_scratchpad_tag(),
             workspace_columns: 3usize,
             workspaces: vec![
                 "upper_left".to_string(), "upper_mid".to_string(), "upper_right".to_string(), 
                 "mid_left".to_string(), "mid_mid".to_string(), "mid_right".to_string(), 
                 "bottom_left".to_string(), "bottom_mid".to_string(), "bottom_right".to_string(), 
             ],
-            monitor_resolution: Size::new(640u32, 480u32)
+            monitor_resolution: Size::new(640u32, 480u32),
+            focus_wrapping: false,
+            policy: default_policy(),
+            master_count: default_master_count(),
+            master_ratio: default_master_ratio(),
+            workspace_policies: HashMap::new(),
+            templates: HashMap::new()
         }
     }
 }
@@ -49,42 +106,98 @@ impl LayoutConfig {
         self.workspaces.len() / self.workspace_columns
     }
 
+    /// The tag of the workspace at a position of the grid - as configured within the initial grid, numbered beyond it
+    pub fn workspace_tag(&self, row: usize, column: usize) -> String{
+        if row < self.grid_height() && column < self.grid_width() {
+            if let Some(tag) = self.workspaces.get(row * self.grid_width() + column) {
+                return tag.clone();
+            }
+        }
+
+        format!("workspace_{}_{}", row, column)
+    }
+
+    /// Whether a tag is one given to workspaces by their position
+    pub fn is_workspace_tag(&self, tag: &str) -> bool{
+        self.workspaces.iter().any(|workspace_tag| workspace_tag == tag) || tag.starts_with("workspace_")
+    }
+
     pub fn monitor_geometry(&self) -> Geometry{
         Geometry::new(Point::origin(), self.monitor_resolution) 
     }
 
-    pub fn construct_tree(&self) -> LayoutTree{
+    /// The layout policy going by a name, set up according to the config
+    pub fn make_policy(&self, name: &str) -> Option<Box<LayoutPolicy>>{
+        match name {
+            "auto_circulation" => Some(box AutoCirculation::init(self.workspaces.len())),
+            "master_stack" => Some(box MasterStack::init(self.master_count, self.master_ratio)),
+            _ => None
+        }
+    }
+
+    pub fn construct_tree(&self) -> Result<LayoutTree, String>{
         let grid_w = self.grid_width();
         let grid_h = self.grid_height();
 
         let mut tree = LayoutTree::init(self.monitor_geometry(), grid_w, grid_h);
 
+        match self.make_policy(&self.policy) {
+            Some(policy) => tree.layout_policy = policy,
+            None => println!("Unknown layout policy '{}', using the default one.", self.policy)
+        }
+
         tree.tags.tag_element_on_condition(&self.root_tag, |elem_id, _| elem_id == PARENT_ELEMENT);
         tree.tags.tag_element_on_condition(&self.focused_tag, |elem_id, wm_state| elem_id == wm_state.tree.focused_id);
 
         // Root element
-        let (root_ident, root_profile) = Padding::init(tree.spawn_dummy_element(None), &mut tree, 100, None);
+        let (root_ident, root_profile) = Padding::init(tree.spawn_dummy_element(None)?, &mut tree, 100, None)?;
 
         // Jumper element
-        let (jumper_ident, jumper_profile) = Padding::init(root_profile.child_elem_id, &mut tree, 0, Some(Point::origin()));
+        let (jumper_ident, jumper_profile) = Padding::init(root_profile.child_elem_id, &mut tree, 0, Some(Point::origin()))?;
         tree.tags.tag_element(&self.jumper_tag, jumper_ident);
 
         // Workspaces
-        let (grid_ident, grid_profile) = Grid::init(jumper_profile.child_elem_id, &mut tree, grid_w, grid_h);
+        let (grid_ident, grid_profile) = Grid::init(jumper_profile.child_elem_id, &mut tree, grid_w, grid_h)?;
         tree.tags.tag_element(&self.grid_tag, grid_ident);
         for (index, child_ident) in grid_profile.children_iter().enumerate(){
             if let Some(tag) = self.workspaces.get(index){
                 tree.tags.tag_element(&tag, *child_ident);
                 tree.tags.tag_element("sub", *child_ident);
+
+                if let Some(policy_name) = self.workspace_policies.get(tag) {
+                    match self.make_policy(policy_name) {
+                        Some(policy) => tree.set_policy_of(*child_ident, policy),
+                        None => println!("Unknown layout policy '{}' for workspace '{}'.", policy_name, tag)
+                    }
+                }
+
+                if let Some(template) = self.templates.get(tag) {
+                    let content_ident = match tree.try_lookup_element(*child_ident)? {
+                        Some(workspace) => match workspace.profile {
+                            LayoutElementProfile::Padding(ref padding) => Some(padding.child_elem_id),
+                            _ => None
+                        },
+                        None => None
+                    };
+
+                    if let Some(content_ident) = content_ident {
+                        template.construct(&mut tree, content_ident)?;
+                    }
+                }
             }
         }
 
-        tree.reserve_element_identity(root_ident, LayoutElementProfile::Padding(root_profile));
-        tree.reserve_element_identity(jumper_ident, LayoutElementProfile::Padding(jumper_profile));
-        tree.reserve_element_identity(grid_ident, LayoutElementProfile::Grid(grid_profile));
+        // Scratchpad element - kept apart from the root so that nothing in it is arranged
+        let (scratchpad_ident, scratchpad_profile) = Padding::init(tree.spawn_dummy_element(None)?, &mut tree, 0, None)?;
+        tree.tags.tag_element(&self.scratchpad_tag, scratchpad_ident);
+
+        tree.reserve_element_identity(root_ident, LayoutElementProfile::Padding(root_profile))?;
+        tree.reserve_element_identity(jumper_ident, LayoutElementProfile::Padding(jumper_profile))?;
+        tree.reserve_element_identity(grid_ident, LayoutElementProfile::Grid(grid_profile))?;
+        tree.reserve_element_identity(scratchpad_ident, LayoutElementProfile::Padding(scratchpad_profile))?;
 
-        tree.animate_property(root_ident, "gap_size", 0f32, false, 250);
+        tree.animate_property(root_ident, "gap_size", 0f32, false, 250)?;
 
-        tree
+        Ok(tree)
     }
 }
\ No newline at end of file
@@ -21,11 +21,32 @@ use self::keyboard::KeyboardConfig;
 mod layout;
 use self::layout::LayoutConfig;
 
+mod window;
+use self::window::WindowConfig;
+
+mod session;
+use self::session::SessionConfig;
+
+mod template;
+
+pub mod rules;
+use self::rules::Rule;
+
 #[derive(Serialize, Deserialize)]
 pub struct Config {
     pub background: BackgroundConfig,
     pub keyboard: KeyboardConfig,
-    pub layout: LayoutConfig
+    pub layout: LayoutConfig,
+
+    #[serde(default)]
+    pub window: WindowConfig,
+
+    #[serde(default)]
+    pub session: SessionConfig,
+
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<Rule>
 }
 
 impl Default for Config {
@@ -33,7 +54,10 @@ impl Default for Config {
         Config {
             background: BackgroundConfig::default(),
             keyboard: KeyboardConfig::default(),
-            layout: LayoutConfig::default()
+            layout: LayoutConfig::default(),
+            window: WindowConfig::default(),
+            session: SessionConfig::default(),
+            rules: Vec::new()
         }
     }
 }
@@ -64,8 +88,12 @@ impl Config{
                 let mut contents = String::new();
                 valid_config_file.read_to_string(&mut contents);
                 
-                match toml::from_str(&contents) {
-                    Ok(text) => Some(text),
+                match toml::from_str::<Config>(&contents) {
+                    Ok(mut config) => {
+                        config.keyboard.merge_default_commands();
+                        config.rules = rules::compile(config.rules);
+                        Some(config)
+                    },
                     Err(_) => None
                 }
             },
@@ -0,0 +1,217 @@
+use serde::ser::Serialize;
+use serde::de::Deserialize;
+
+use regex::Regex;
+
+/*
+    A rule for new windows: every condition given has to match for the consequences to apply
+*/
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Rule {
+    pub name: Option<String>,
+
+    // Conditions
+    pub app_id: Option<String>,
+    pub title: Option<String>,
+    pub command: Option<String>,
+
+    // Consequences
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub workspace: Option<String>,
+    // the name of a slot in a workspace template, taken if it is unoccupied
+    pub slot: Option<String>,
+    pub floating: Option<bool>,
+    pub fullscreen: Option<bool>,
+    pub gap_size: Option<u32>,
+    pub frame_opacity: Option<f32>,
+
+    // The patterns of 'title' and 'command', compiled once the config is loaded
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    title_regex: Option<Regex>,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    command_regex: Option<Regex>
+}
+
+impl Rule {
+    /// Compiles the patterns of the rule, failing on the first invalid one
+    pub fn compile_patterns(&mut self) -> Result<(), String> {
+        let compile = |pattern: &Option<String>| match *pattern {
+            Some(ref pattern) => Regex::new(pattern).map(Some).map_err(|e| format!("Invalid pattern '{}': {}", pattern, e)),
+            None => Ok(None)
+        };
+
+        self.title_regex = compile(&self.title)?;
+        self.command_regex = compile(&self.command)?;
+
+        Ok(())
+    }
+
+    /// Whether the rule applies to a window - 'title' and 'command' are regular expressions, 'app_id' has to be equal.
+    /// A pattern that hasn't been compiled matches nothing.
+    pub fn matches(&self, app_id: &str, title: &str, command: Option<&str>) -> bool {
+        let matches_pattern = |pattern: &Option<String>, regex: &Option<Regex>, text: Option<&str>| {
+            match (pattern, regex, text) {
+                (&None, _, _) => true,
+                (&Some(_), &Some(ref regex), Some(text)) => regex.is_match(text),
+                _ => false
+            }
+        };
+
+        let no_conditions = self.app_id.is_none() && self.title.is_none() && self.command.is_none();
+
+        !no_conditions &&
+        self.app_id.as_ref().map_or(true, |expected| expected == app_id) &&
+        matches_pattern(&self.title, &self.title_regex, Some(title)) &&
+        matches_pattern(&self.command, &self.command_regex, command)
+    }
+
+    pub fn display_name(&self, index: usize) -> String {
+        match self.name {
+            Some(ref name) => name.clone(),
+            None => format!("#{}", index)
+        }
+    }
+}
+
+/// Compiles the patterns of the rules of a config - a rule with an invalid pattern is left out
+pub fn compile(rules: Vec<Rule>) -> Vec<Rule> {
+    let mut compiled = Vec::new();
+
+    for (index, mut rule) in rules.into_iter().enumerate() {
+        match rule.compile_patterns() {
+            Ok(_) => compiled.push(rule),
+            Err(e) => println!("Left out rule {} of the config: {}", rule.display_name(index), e)
+        }
+    }
+
+    compiled
+}
+
+/// The rules (and their indices) that apply to a window, in the order of the config
+pub fn matching_rules<'a>(rules: &'a [Rule], app_id: &str, title: &str, command: Option<&str>) -> Vec<(usize, &'a Rule)> {
+    rules.iter().enumerate().filter(|&(_, rule)| rule.matches(app_id, title, command)).collect()
+}
+
+/// What the matching rules of a window amount to - later rules override earlier ones
+pub struct RuleOutcome {
+    pub matched: Vec<String>,
+    pub tags: Vec<String>,
+    pub workspace: Option<String>,
+    pub slot: Option<String>,
+    pub floating: Option<bool>,
+    pub fullscreen: Option<bool>,
+    pub gap_size: Option<u32>,
+    pub frame_opacity: Option<f32>
+}
+
+pub fn resolve(rules: &[Rule], app_id: &str, title: &str, command: Option<&str>) -> RuleOutcome {
+    let mut outcome = RuleOutcome {
+        matched: Vec::new(),
+        tags: Vec::new(),
+        workspace: None,
+        slot: None,
+        floating: None,
+        fullscreen: None,
+        gap_size: None,
+        frame_opacity: None
+    };
+
+    for (index, rule) in matching_rules(rules, app_id, title, command) {
+        outcome.matched.push(rule.display_name(index));
+        outcome.tags.extend(rule.tags.iter().cloned());
+        outcome.workspace = rule.workspace.clone().or(outcome.workspace);
+        outcome.slot = rule.slot.clone().or(outcome.slot);
+        outcome.floating = rule.floating.or(outcome.floating);
+        outcome.fullscreen = rule.fullscreen.or(outcome.fullscreen);
+        outcome.gap_size = rule.gap_size.or(outcome.gap_size);
+        outcome.frame_opacity = rule.frame_opacity.or(outcome.frame_opacity);
+    }
+
+    outcome
+}
+
+#[cfg(test)]
+mod test{
+    use super::{Rule, compile, resolve};
+
+    fn rule(name: &str, app_id: Option<&str>, title: Option<&str>, command: Option<&str>) -> Rule {
+        Rule {
+            name: Some(String::from(name)),
+            app_id: app_id.map(String::from),
+            title: title.map(String::from),
+            command: command.map(String::from),
+            tags: Vec::new(),
+            workspace: None,
+            slot: None,
+            floating: None,
+            fullscreen: None,
+            gap_size: None,
+            frame_opacity: None,
+            title_regex: None,
+            command_regex: None
+        }
+    }
+
+    #[test]
+    fn rule_matching_test(){
+        let rules = compile(vec![
+            rule("browser", Some("firefox"), None, None),
+            rule("editor", None, Some("^vim? "), None),
+            rule("music", None, None, Some("mpv --no-video")),
+            rule("nothing", None, None, None)
+        ]);
+        let names = |app_id, title, command| -> Vec<String> {
+            resolve(&rules, app_id, title, command).matched
+        };
+
+        assert_eq!(names("firefox", "Mozilla Firefox", None), vec![String::from("browser")]);
+        assert!(names("firefox-esr", "Mozilla Firefox", None).is_empty());
+        assert_eq!(names("termite", "vim notes.md", None), vec![String::from("editor")]);
+        assert!(names("termite", "nvim notes.md", None).is_empty());
+
+        // A command pattern doesn't match a window whose command isn't known
+        assert_eq!(names("mpv", "mpv", Some("/usr/bin/mpv --no-video song.ogg")), vec![String::from("music")]);
+        assert!(names("mpv", "mpv", None).is_empty());
+
+        // A rule without conditions applies to nothing
+        assert!(names("", "", None).is_empty());
+
+        // A pattern that hasn't been compiled matches nothing either
+        assert!(!rule("editor", None, Some("vim"), None).matches("termite", "vim", None));
+    }
+
+    #[test]
+    fn rule_outcome_test(){
+        let mut first = rule("first", Some("termite"), None, None);
+        first.tags = vec![String::from("shell")];
+        first.workspace = Some(String::from("dev"));
+        first.floating = Some(true);
+
+        let mut second = rule("second", None, Some("htop"), None);
+        second.tags = vec![String::from("monitor")];
+        second.workspace = Some(String::from("system"));
+
+        let rules = compile(vec![first, second]);
+
+        // Later rules override earlier ones, the tags add up
+        let outcome = resolve(&rules, "termite", "htop", None);
+        assert_eq!(outcome.matched, vec![String::from("first"), String::from("second")]);
+        assert_eq!(outcome.tags, vec![String::from("shell"), String::from("monitor")]);
+        assert_eq!(outcome.workspace, Some(String::from("system")));
+        assert_eq!(outcome.floating, Some(true));
+    }
+
+    #[test]
+    fn invalid_rule_test(){
+        let mut invalid = rule("invalid", None, Some("(unclosed"), None);
+        assert!(invalid.compile_patterns().is_err());
+
+        let rules = compile(vec![rule("valid", Some("firefox"), None, None), invalid, rule("also invalid", None, None, Some("[z-a]"))]);
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].display_name(0), "valid");
+    }
+}
@@ -0,0 +1,16 @@
+use serde::ser::Serialize;
+use serde::de::Deserialize;
+
+#[derive(Serialize, Deserialize)]
+pub struct SessionConfig {
+    // whether the session is saved on exit, and its applications relaunched into their places on startup
+    pub restore: bool
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        SessionConfig {
+            restore: false
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use serde::ser::Serialize;
+use serde::de::Deserialize;
+
+use common::definitions::LayoutElemID;
+use layout::LayoutTree;
+use layout::element::LayoutElementProfile;
+use layout::element::padding::Padding;
+use layout::element::bisect::{Bisect, Orientation, MIN_RATIO, MAX_RATIO};
+use layout::element::split::Split;
+
+/*
+    A subtree that a workspace starts out with - a container when it has children, otherwise an unoccupied slot
+*/
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TemplateNode {
+    // The name of a slot, for the 'slot' of [[rules]] to put windows in it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slot: Option<String>,
+
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    // A gap of this size around the node
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub padding: Option<u32>,
+
+    // 'Horizontal' or 'Vertical' - whether the children are arranged in a row or a column
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub split: Option<Orientation>,
+
+    // The share of the first of two children, or the weights of any number of children
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ratio: Option<f32>,
+    #[serde(default)]
+    pub weights: Vec<f32>,
+
+    #[serde(default)]
+    pub children: Vec<TemplateNode>
+}
+
+impl TemplateNode {
+    /// Builds the node in place of an unoccupied element
+    pub fn construct(&self, tree: &mut LayoutTree, ident: LayoutElemID) -> Result<(), String> {
+        for tag in self.tags.iter() {
+            tree.tags.bind_tag(tag, ident);
+        }
+
+        if let Some(gap_size) = self.padding {
+            let (_, padding) = Padding::init(ident, tree, gap_size, None)?;
+            let inner_ident = padding.child_elem_id;
+            tree.reserve_element_identity(ident, LayoutElementProfile::Padding(padding))?;
+
+            // The rest of the node goes inside of the padding
+            let inner = TemplateNode{ tags: Vec::new(), padding: None, ..self.clone() };
+            return inner.construct(tree, inner_ident);
+        }
+
+        let orientation = self.split.unwrap_or(Orientation::Horizontal);
+
+        let child_ids = match self.children.len() {
+            0 => {
+                if let Some(ref slot) = self.slot {
+                    tree.tags.bind_tag(slot, ident);
+                }
+                return Ok(());
+            },
+            1 => return self.children[0].construct(tree, ident),
+            2 if self.weights.is_empty() => {
+                let ratio = self.ratio.unwrap_or(0.5f32).max(MIN_RATIO).min(MAX_RATIO);
+                let (_, bisect) = Bisect::init(ident, tree, orientation, ratio)?;
+                let child_ids: Vec<LayoutElemID> = bisect.children_iter().cloned().collect();
+
+                tree.reserve_element_identity(ident, LayoutElementProfile::Bisect(bisect))?;
+                child_ids
+            },
+            count => {
+                let (_, mut split) = Split::init(ident, tree, orientation, count)?;
+                for (i, &weight) in self.weights.iter().enumerate() {
+                    split.set_weight(i, weight);
+                }
+                let child_ids = split.get_all_children().clone();
+
+                tree.reserve_element_identity(ident, LayoutElementProfile::Split(split))?;
+                child_ids
+            }
+        };
+
+        for (&child_ident, child) in child_ids.iter().zip(self.children.iter()) {
+            // Recursion
+            child.construct(tree, child_ident)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,20 @@
+use serde::ser::Serialize;
+use serde::de::Deserialize;
+
+#[derive(Serialize, Deserialize)]
+pub struct WindowConfig {
+    // milliseconds given to a window to close by itself before it is killed
+    pub kill_timeout: u64,
+
+    // the signal (as understood by 'kill -s') that is sent to windows not closing in time
+    pub kill_signal: String
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        WindowConfig {
+            kill_timeout: 2000u64,
+            kill_signal: "KILL".to_string()
+        }
+    }
+}
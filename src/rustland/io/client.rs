@@ -0,0 +1,79 @@
+use io::submit_job;
+use io::event::subscribe;
+use common::job::{Job, JobType};
+use common::protocol::{handshake, read_frame, write_message};
+
+use std::io::{Read, Write};
+use std::thread::spawn;
+
+use serde_json;
+
+/// Serves a connected client (TCP or Unix) until it hangs up
+pub fn handle_client<S: Read + Write + Send + 'static>(mut stream: S, client_name: String) {
+    if let Err(e) = handshake(&mut stream) {
+        println!("Client {} rejected: {}", client_name, e);
+        return;
+    }
+
+    println!("Client {} connected. Now ready accept jobs it.", client_name);
+
+    loop{
+        let payload = match read_frame(&mut stream) {
+            Ok(Some(payload)) => payload,
+            Ok(None) => break,
+            Err(e) => {
+                // The stream is either dead or out of step with the frames, nothing after this can be trusted
+                println!("Client {}: Couldn't read message: {}", client_name, e);
+                break;
+            }
+        };
+
+        let job = match serde_json::from_slice::<Job>(&payload) {
+            Ok(job) => job,
+            Err(e) => {
+                // The frame itself was intact, so let the client know why nothing happened and carry on
+                let mut rejected_job = Job::default();
+                rejected_job.generated_result = Err(format!("Malformed message: {}", e));
+
+                if write_message(&mut stream, &rejected_job).is_err() { break; }
+                continue;
+            }
+        };
+
+        if job.job_type == JobType::SUBSCRIBE {
+            // The connection is handed over to an event stream for the rest of its lifetime
+            spawn(move || stream_events(stream, client_name, job.anonymous_args));
+            return;
+        }
+
+        // The job routine of the compositor processes it, this thread only waits for the outcome
+        let result = submit_job(job);
+
+        match result.recv() {
+            Ok(job) => {
+                if let Err(e) = write_message(&mut stream, &job) {
+                    println!("Client {}: {}", client_name, e);
+                    break;
+                }
+            },
+            Err(_) => break
+        }
+    }
+}
+
+/// Pushes every event (optionally only those named in 'filter') to the client as JSON
+fn stream_events<S: Read + Write>(mut stream: S, client_name: String, filter: Vec<String>) {
+    println!("Client {} subscribed to events: {}", client_name, if filter.is_empty() { String::from("all") } else { filter.join(", ") });
+
+    let events = subscribe();
+
+    for event in events.iter() {
+        if !filter.is_empty() && !filter.iter().any(|name| name == event.name()) { 
+            continue; 
+        }
+
+        if write_message(&mut stream, &event).is_err() { break; }
+    }
+
+    println!("Client {} unsubscribed.", client_name);
+}
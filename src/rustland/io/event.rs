@@ -0,0 +1,25 @@
+use common::event::Event;
+
+use std::sync::Mutex;
+use std::sync::mpsc::{channel, Sender, Receiver};
+
+lazy_static! {
+    static ref SUBSCRIBERS: Mutex<Vec<Sender<Event>>> = Mutex::new(Vec::new());
+}
+
+pub fn subscribe() -> Receiver<Event> {
+    let (sender, receiver) = channel();
+
+    if let Ok(mut subscribers) = SUBSCRIBERS.lock() {
+        subscribers.push(sender);
+    }
+
+    receiver
+}
+
+pub fn broadcast(event: Event) {
+    if let Ok(mut subscribers) = SUBSCRIBERS.lock() {
+        // Subscribers that hung up are forgotten about
+        subscribers.retain(|subscriber| subscriber.send(event.clone()).is_ok());
+    }
+}
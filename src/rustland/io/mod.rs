@@ -1,46 +1,244 @@
-use std::process::Command;
-
-use common::definitions::ElementReference;
+use common::definitions::{ElementReference, LayoutElemID, ViewPID};
 use common::job::{Job, JobType};
-use wmstate::{WM_STATE, PENDING_JOBS, FINALIZED_JOBS};
+use common::event::Event;
+use config::Config;
+use config::rules;
+use io::event::broadcast;
+use sugars::solid_color::SolidColor;
+use async::schedule_wallpaper_init;
+use wmstate::{WM_STATE, PENDING_JOBS};
 use layout::element::{LayoutElement, LayoutElementProfile};
+use layout::element::grid::{GridAxis, Direction};
+use layout::element::bisect::{Orientation, MIN_RATIO, MAX_RATIO};
+use layout::element::window::WindowMode;
+use layout::preselection::Preselection;
 use layout::arrangement;
+use layout::navigation;
+use layout::floating;
+use layout::scratchpad;
+use layout::compaction;
 use layout::tag::TagRegister;
-use layout::LayoutTree;
+use layout::snapshot::LayoutSnapshot;
+use layout::{LayoutTree, PARENT_ELEMENT};
+
+use std::path::PathBuf;
+use std::thread::{sleep, spawn};
+use std::time::Duration;
+use std::sync::mpsc::{channel, Sender, Receiver};
+
+use serde_json;
+use libc;
+use serde_json::Value;
 
 use wlc::ViewState;
 
 pub mod physical;
+pub mod client;
+pub mod event;
 pub mod tcp_server;
+pub mod unix_server;
+
+/// Queues a job without anyone waiting for its result
+pub fn enqueue_job(job: Job){
+    if let Ok(mut pending_jobs) = PENDING_JOBS.lock(){
+        pending_jobs.push((job, None));
+    }
+}
+
+/// Queues a job, the processed job (with its generated result) is sent back through the receiver
+pub fn submit_job(job: Job) -> Receiver<Job>{
+    let (sender, receiver) = channel();
+
+    if let Ok(mut pending_jobs) = PENDING_JOBS.lock(){
+        pending_jobs.push((job, Some(sender)));
+    }
+
+    receiver
+}
 
 pub fn process_all_current_jobs(){
-    if let Ok(mut pending_jobs) = PENDING_JOBS.try_lock(){
-        if let Ok(mut finalized_jobs) = FINALIZED_JOBS.try_lock(){
-            while let Some(mut job) = pending_jobs.pop(){
-                let result = process_job(&job);
-                job.generated_result =  result;
+    // The queue is only locked while being emptied, so that submitting never waits for processing
+    let jobs: Vec<(Job, Option<Sender<Job>>)> = match PENDING_JOBS.lock(){
+        Ok(mut pending_jobs) => pending_jobs.drain(..).collect(),
+        Err(_) => return
+    };
 
-                match job.generated_result {
-                    Ok(ref expected_result) => println!("Notice: A job described as '{}' has been processed.", format!("{}", job.job_type).to_lowercase()),
-                    Err(ref e) => println!("Couldn't process job request: {}, cause: {}", job.job_type, e.to_lowercase())
-                }
+    for (mut job, reply_to) in jobs{
+        // Killing waits for the windows to comply, which mustn't hold up the jobs behind it
+        if job.job_type == JobType::KILL {
+            kill_windows(job, reply_to);
+            continue;
+        }
 
-                finalized_jobs.push(job);
+        let result = process_job(&job);
+        job.generated_result =  result;
+
+        complete_job(job, reply_to);
+    }
+}
+
+fn complete_job(job: Job, reply_to: Option<Sender<Job>>){
+    match job.generated_result {
+        Ok(ref expected_result) => println!("Notice: A job described as '{}' has been processed.", format!("{}", job.job_type).to_lowercase()),
+        Err(ref e) => println!("Couldn't process job request: {}, cause: {}", job.job_type, e.to_lowercase())
+    }
+
+    if let Some(sender) = reply_to{
+        // The client may have hung up in the meantime
+        let _ = sender.send(job);
+    }
+}
+
+/// Asks the windows to close and, on a thread of its own, sends a signal to the processes whose windows are still shown after the timeout
+fn kill_windows(mut job: Job, reply_to: Option<Sender<Job>>){
+    // The lock is released before waiting for the windows to comply
+    let prepared = match WM_STATE.read() {
+        Ok(wm_state) => match job.main_ref {
+            Some(ref main_ref) => match signal_number(&wm_state.config.window.kill_signal) {
+                Some(signal) => close_windows(&wm_state.tree, main_ref.clone()).map(|closed| (closed, wm_state.config.window.kill_timeout, wm_state.config.window.kill_signal.clone(), signal)),
+                None => Err(format!("Unknown signal '{}' in the config.", wm_state.config.window.kill_signal))
+            },
+            None => Err(String::from("Kill what?"))
+        },
+        Err(_) => Err(String::from("Couldn't get a lock on wmstate!"))
+    };
+
+    let (closed, timeout, signal_name, signal) = match prepared {
+        Ok((ref closed, _, _, _)) if closed.is_empty() => {
+            job.generated_result = Err(String::from("There are no windows to kill there."));
+            return complete_job(job, reply_to);
+        },
+        Ok(prepared) => prepared,
+        Err(e) => {
+            job.generated_result = Err(e);
+            return complete_job(job, reply_to);
+        }
+    };
+
+    spawn(move || {
+        let poll_interval = 50;
+        let mut waited = 0;
+        while waited < timeout && closed.iter().any(|&(_, pid)| process_is_alive(pid)) {
+            sleep(Duration::from_millis(poll_interval));
+            waited += poll_interval;
+        }
+
+        // Only the processes of windows that are still there are signalled - the pid of a process that is gone may have been handed on
+        let remaining = match WM_STATE.read() {
+            Ok(wm_state) => remaining_pids(&wm_state.tree, &closed),
+            Err(_) => Err(String::from("Couldn't get a lock on wmstate!"))
+        };
+        let remaining = match remaining {
+            Ok(remaining) => remaining,
+            Err(e) => {
+                job.generated_result = Err(e);
+                return complete_job(job, reply_to);
+            }
+        };
+
+        let mut killed = Vec::new();
+        let mut failed = Vec::new();
+        for pid in remaining {
+            match unsafe { libc::kill(pid as libc::pid_t, signal) } {
+                0 => killed.push(pid),
+                _ => failed.push(pid)
+            }
+        }
+
+        job.generated_result = match failed.is_empty() {
+            true => Ok(format!("Windows closed - the processes {:?} had to be sent SIG{}.", killed, signal_name)),
+            false => Err(format!("Couldn't send SIG{} to the processes {:?}.", signal_name, failed))
+        };
+
+        complete_job(job, reply_to);
+    });
+}
+
+/// Asks every window at (or beneath) the referenced elements to close and returns them along with their pids
+fn close_windows(tree: &LayoutTree, reference: ElementReference) -> Result<Vec<(LayoutElemID, ViewPID)>, String> {
+    let mut window_ids = Vec::new();
+    for element_id in tree.tags.address_element(reference) {
+        arrangement::find_all_windows(&mut window_ids, false, tree, element_id)?;
+    }
+
+    let mut closed = Vec::new();
+    for window_id in window_ids {
+        if let Some(element) = tree.try_lookup_element(window_id)? {
+            if let LayoutElementProfile::Window(ref window) = element.profile {
+                if let Some(view) = window.get_view() {
+                    closed.push((window_id, view.pid()));
+                    view.close();
+                }
             }
-        }     
+        }
+    }
+
+    Ok(closed)
+}
+
+/// The pids of the closed windows that are still shown by the same process, without duplicates
+fn remaining_pids(tree: &LayoutTree, closed: &[(LayoutElemID, ViewPID)]) -> Result<Vec<ViewPID>, String> {
+    let mut remaining = Vec::new();
+    for &(window_id, pid) in closed {
+        let still_shown = match tree.try_lookup_element(window_id)? {
+            Some(element) => match element.profile {
+                LayoutElementProfile::Window(ref window) => window.get_view().map_or(false, |view| view.pid() == pid),
+                _ => false
+            },
+            None => false
+        };
+
+        if still_shown && process_is_alive(pid) && !remaining.contains(&pid) {
+            remaining.push(pid);
+        }
+    }
+
+    Ok(remaining)
+}
+
+fn process_is_alive(pid: ViewPID) -> bool {
+    // Pids below 2 would address process groups or init
+    pid > 1 && unsafe { libc::kill(pid as libc::pid_t, 0) } == 0
+}
+
+/// The number of a signal going by its name (without the SIG prefix) or by its number
+fn signal_number(name: &str) -> Option<libc::c_int> {
+    match name.trim_left_matches("SIG") {
+        "HUP" => Some(libc::SIGHUP),
+        "INT" => Some(libc::SIGINT),
+        "QUIT" => Some(libc::SIGQUIT),
+        "ABRT" => Some(libc::SIGABRT),
+        "KILL" => Some(libc::SIGKILL),
+        "USR1" => Some(libc::SIGUSR1),
+        "USR2" => Some(libc::SIGUSR2),
+        "TERM" => Some(libc::SIGTERM),
+        "CONT" => Some(libc::SIGCONT),
+        "STOP" => Some(libc::SIGSTOP),
+        other => other.parse::<libc::c_int>().ok()
+    }
+}
+
+fn parse_grid_axis(job: &Job) -> Result<GridAxis, String>{
+    match job.anonymous_args.first().map(|arg| arg.as_str()) {
+        Some("row") | None => Ok(GridAxis::Row),
+        Some("column") => Ok(GridAxis::Column),
+        Some(other) => Err(format!("Expected 'row' or 'column', not '{}'.", other))
     }
 }
 
 fn process_job(job: &Job) -> Result<String, String>{
     match job.job_type
     {
-        JobType::NA => { panic!("WTF") }
+        JobType::NA => Err(String::from("The request didn't name anything to do.")),
         JobType::FOCUS => {
             if let Ok(mut wm_state) = WM_STATE.write() { 
-                if let Some(ref main_ref) = job.main_ref{
+                if let Some(direction) = job.anonymous_args.first().and_then(|arg| Direction::parse(arg)){
+                    navigation::focus_in_direction(&mut wm_state, direction)
+                }
+                else if let Some(ref main_ref) = job.main_ref{
                     if let Some(target_element_id) = wm_state.tree.tags.address_element(main_ref.clone()).first().cloned(){            
                         for (view_id, elem_id) in wm_state.tree.tags.view_bindings.iter(){
-                            if let Some(mut element) = wm_state.tree.lookup_element(*elem_id){
+                            if let Some(mut element) = wm_state.tree.try_lookup_element(*elem_id)?{
                                 match element.profile
                                 {
                                     LayoutElementProfile::Window(ref mut window) => {
@@ -65,6 +263,16 @@ fn process_job(job: &Job) -> Result<String, String>{
                         wm_state.tree.focused_id = target_element_id;
                         TagRegister::refresh_tag_statuses(&mut wm_state);
 
+                        // A window in a hidden tab is brought to the front
+                        if arrangement::reveal_element(&wm_state.tree, target_element_id)? {
+                            LayoutTree::refresh(&mut wm_state);
+                        }
+
+                        broadcast(Event::Focus { 
+                            element_id: target_element_id, 
+                            pid: match *main_ref { ElementReference::ViewPID(pid) => Some(pid), _ => None }
+                        });
+
                         Ok(String::from("Focused changed."))
                     }
                     else{
@@ -85,24 +293,62 @@ fn process_job(job: &Job) -> Result<String, String>{
             Ok(String::from("Layout refreshed."))
         },
         JobType::WORKSPACE_INSERT => {
-            Err(String::from("Unimplemented :("))
+            match parse_grid_axis(job) {
+                Ok(axis) => {
+                    if let Ok(mut wm_state) = WM_STATE.write() {
+                        arrangement::insert_workspaces(&mut wm_state, axis)
+                    }
+                    else { Err(String::from("Couldn't get a lock on wmstate!")) }
+                },
+                Err(e) => Err(e)
+            }
+        },
+        JobType::WORKSPACE_REMOVE => {
+            match parse_grid_axis(job) {
+                Ok(axis) => {
+                    if let Ok(mut wm_state) = WM_STATE.write() {
+                        // Without a reference the active workspace is removed
+                        let workspace_ident = match job.main_ref {
+                            Some(ref main_ref) => wm_state.tree.tags.address_element(main_ref.clone()).first().cloned(),
+                            None => wm_state.tree.active_workspace()?.map(|(_, ident)| ident)
+                        };
+
+                        match workspace_ident {
+                            Some(workspace_ident) => arrangement::remove_workspaces(&mut wm_state, workspace_ident, axis),
+                            None => Err(String::from("That workspace does not exist in the layout."))
+                        }
+                    }
+                    else { Err(String::from("Couldn't get a lock on wmstate!")) }
+                },
+                Err(e) => Err(e)
+            }
         },
         JobType::RUN_APP => {
-            if let Some(application) = job.anonymous_args.first().cloned(){
-                let _ = Command::new("sh")
-                    .arg("-c")
-                    .arg(application)
-                    .spawn()
-                    .unwrap_or_else(|e| {
-                        panic!("Can't spawn process!");
-                    });
+            match job.anonymous_args.first().cloned() {
+                Some(application) => match WM_STATE.write() {
+                    Ok(mut wm_state) => wm_state.launch(application).map(|pid| format!("Application started ({}).", pid)),
+                    Err(_) => Err(String::from("Couldn't get a lock on wmstate!"))
+                },
+                None => Err(String::from("Run what?"))
             }
-
-            Ok(String::from("Application started."))
         },
         JobType::SEND_TREE => {
             if let Ok(mut wm_state) = WM_STATE.write() {
-                Ok(format!("{}", wm_state.tree))
+                if job.anonymous_args.iter().any(|arg| arg == "--json") {
+                    let tree = arrangement::tree_json(&wm_state.tree, PARENT_ELEMENT)?;
+                    let document = json!({
+                        "active_workspace": match wm_state.tree.active_workspace()? {
+                            Some((index, element_id)) => json!({ "index": index, "id": element_id }),
+                            None => Value::Null
+                        },
+                        "tree": tree
+                    });
+
+                    serde_json::to_string_pretty(&document).map_err(|e| format!("Couldn't serialize the tree: {}", e))
+                }
+                else {
+                    Ok(format!("{}", wm_state.tree))
+                }
             }
             else { Err(String::from("Couldn't get a lock on wmstate!")) }
             
@@ -113,13 +359,394 @@ fn process_job(job: &Job) -> Result<String, String>{
                     Err(String::from("Move what, to where?"))
                 }
                 else{
-                    let carry_id = wm_state.tree.tags.address_element(job.main_ref.clone().unwrap_or(ElementReference::ElementID(0))).first().cloned().unwrap_or(0);
-                    let dest_id = wm_state.tree.tags.address_element(job.contextual_refs.first().cloned().unwrap_or(ElementReference::ElementID(0))).first().cloned().unwrap_or(0);
+                    let carry_id = wm_state.tree.tags.address_element(job.main_ref.clone().unwrap()).first().cloned();
+                    let dest_id = wm_state.tree.tags.address_element(job.contextual_refs.first().cloned().unwrap()).first().cloned();
 
-                    arrangement::move_element(&mut wm_state, carry_id, dest_id)
+                    match (carry_id, dest_id) {
+                        (Some(carry_id), Some(dest_id)) => navigation::move_element(&mut wm_state, carry_id, dest_id),
+                        (None, _) => Err(String::from("The element to move does not exist in the layout.")),
+                        (_, None) => Err(String::from("The destination does not exist in the layout."))
+                    }
                 }
             }
             else { Err(String::from("Couldn't get a lock on wmstate!")) }
+        },
+        JobType::SWAP => {
+            if let Ok(mut wm_state) = WM_STATE.write() {
+                if job.main_ref.is_none() || job.contextual_refs.is_empty(){
+                    Err(String::from("Swap which windows?"))
+                }
+                else{
+                    let first_id = wm_state.tree.tags.address_element(job.main_ref.clone().unwrap()).first().cloned();
+                    let second_id = wm_state.tree.tags.address_element(job.contextual_refs.first().cloned().unwrap()).first().cloned();
+
+                    match (first_id, second_id) {
+                        (Some(first_id), Some(second_id)) => navigation::swap_windows(&mut wm_state, first_id, second_id),
+                        _ => Err(String::from("Both windows must exist in the layout."))
+                    }
+                }
+            }
+            else { Err(String::from("Couldn't get a lock on wmstate!")) }
+        },
+        JobType::RESIZE => {
+            if let Ok(mut wm_state) = WM_STATE.write() {
+                let element_id = match job.main_ref {
+                    Some(ref main_ref) => wm_state.tree.tags.address_element(main_ref.clone()).first().cloned(),
+                    None => Some(wm_state.tree.focused_id)
+                };
+
+                let args: Vec<&str> = job.anonymous_args.iter().map(|arg| arg.as_str()).collect();
+                let grow = match args.get(0) {
+                    Some(&"grow") => Some(true),
+                    Some(&"shrink") => Some(false),
+                    _ => None
+                };
+                let direction = args.get(1).and_then(|arg| Direction::parse(arg));
+                let amount = args.get(2).and_then(|arg| arg.parse::<u32>().ok());
+
+                match (element_id, grow, direction, amount) {
+                    (Some(element_id), Some(grow), Some(direction), Some(amount)) => arrangement::resize_element(&mut wm_state, element_id, grow, direction, amount),
+                    (None, _, _, _) => Err(String::from("That element does not exist in the layout.")),
+                    _ => Err(String::from("Usage: [@WINDOW] resize grow|shrink left|right|up|down <amount>"))
+                }
+            }
+            else { Err(String::from("Couldn't get a lock on wmstate!")) }
+        },
+        JobType::SPLIT => {
+            if let Ok(mut wm_state) = WM_STATE.write() {
+                let element_id = match job.main_ref {
+                    Some(ref main_ref) => wm_state.tree.tags.address_element(main_ref.clone()).first().cloned(),
+                    None => Some(wm_state.tree.focused_id)
+                };
+
+                match (element_id, job.anonymous_args.first().map(|arg| arg.as_str())) {
+                    (None, _) => Err(String::from("That element does not exist in the layout.")),
+                    (Some(element_id), Some("horizontal")) => arrangement::split_element(&mut wm_state, element_id, Some(Orientation::Horizontal)),
+                    (Some(element_id), Some("vertical")) => arrangement::split_element(&mut wm_state, element_id, Some(Orientation::Vertical)),
+                    (Some(element_id), Some("toggle")) => arrangement::split_element(&mut wm_state, element_id, None),
+                    (Some(element_id), Some("even")) => arrangement::rebalance_split(&mut wm_state, element_id),
+                    (Some(element_id), Some("flatten")) => arrangement::flatten_split(&mut wm_state, element_id),
+                    _ => Err(String::from("Usage: [@WINDOW] split horizontal|vertical|toggle|even|flatten"))
+                }
+            }
+            else { Err(String::from("Couldn't get a lock on wmstate!")) }
+        },
+        JobType::PRESELECT => {
+            if let Ok(mut wm_state) = WM_STATE.write() {
+                let element_id = match job.main_ref {
+                    Some(ref main_ref) => wm_state.tree.tags.address_element(main_ref.clone()).first().cloned(),
+                    None => Some(wm_state.tree.focused_id)
+                };
+
+                let is_window = match element_id {
+                    Some(element_id) => match wm_state.tree.try_lookup_element(element_id)? {
+                        Some(element) => match element.profile { LayoutElementProfile::Window(_) => true, _ => false },
+                        None => false
+                    },
+                    None => false
+                };
+
+                let direction = job.anonymous_args.get(0).and_then(|arg| Direction::parse(arg));
+                let ratio = job.anonymous_args.get(1).map(|arg| arg.parse::<f32>());
+
+                let result = if job.anonymous_args.first().map_or(false, |arg| arg == "cancel") {
+                    wm_state.tree.preselection = None;
+                    Ok(String::from("Preselection cancelled."))
+                }
+                else if !is_window {
+                    Err(String::from("Only windows can be preselected."))
+                }
+                else {
+                    match (direction, ratio) {
+                        (Some(direction), None) | (Some(direction), Some(Ok(_))) => {
+                            let ratio = match ratio { Some(Ok(ratio)) => ratio.max(MIN_RATIO).min(MAX_RATIO), _ => 0.5f32 };
+                            let target = element_id.unwrap();
+
+                            wm_state.tree.preselection = Some(Preselection::new(target, direction, ratio, wm_state.graphics_program.as_ref()));
+                            Ok(format!("The next window goes {} of [{}].", job.anonymous_args[0], target))
+                        },
+                        _ => Err(String::from("Usage: [@WINDOW] presel left|right|up|down [ratio] | presel cancel"))
+                    }
+                };
+
+                LayoutTree::refresh(&mut wm_state);
+                result
+            }
+            else { Err(String::from("Couldn't get a lock on wmstate!")) }
+        },
+        JobType::CLOSE => {
+            if let Ok(wm_state) = WM_STATE.read() {
+                match job.main_ref {
+                    Some(ref main_ref) => {
+                        let closed = close_windows(&wm_state.tree, main_ref.clone())?;
+
+                        match closed.is_empty() {
+                            true => Err(String::from("There are no windows to close there.")),
+                            false => Ok(format!("Asked {} window(s) to close.", closed.len()))
+                        }
+                    },
+                    None => Err(String::from("Close what?"))
+                }
+            }
+            else { Err(String::from("Couldn't get a lock on wmstate!")) }
+        },
+        JobType::KILL => {
+            // Handled apart by kill_windows, since it waits for the windows to comply
+            Err(String::from("Kill jobs aren't processed in line with the others."))
+        },
+        JobType::FULLSCREEN | JobType::MAXIMIZE => {
+            if let Ok(mut wm_state) = WM_STATE.write() {
+                let element_id = match job.main_ref {
+                    Some(ref main_ref) => wm_state.tree.tags.address_element(main_ref.clone()).first().cloned(),
+                    None => Some(wm_state.tree.focused_id)
+                };
+
+                let mode = match job.job_type { JobType::FULLSCREEN => WindowMode::Fullscreen, _ => WindowMode::Maximized };
+                let (mode, toggle) = match job.anonymous_args.first().map(|arg| arg.as_str()) {
+                    Some("on") => (Ok(mode), false),
+                    Some("off") => (Ok(WindowMode::Tiled), false),
+                    Some("toggle") | None => (Ok(mode), true),
+                    _ => (Err(String::from("Usage: [@WINDOW] fullscreen|maximize [on|off|toggle]")), false)
+                };
+
+                match (element_id, mode) {
+                    (Some(element_id), Ok(mode)) => arrangement::set_window_mode(&mut wm_state, element_id, mode, toggle),
+                    (None, _) => Err(String::from("That element does not exist in the layout.")),
+                    (_, Err(msg)) => Err(msg)
+                }
+            }
+            else { Err(String::from("Couldn't get a lock on wmstate!")) }
+        },
+        JobType::FLOAT => {
+            if let Ok(mut wm_state) = WM_STATE.write() {
+                let element_id = match job.main_ref {
+                    Some(ref main_ref) => wm_state.tree.tags.address_element(main_ref.clone()).first().cloned(),
+                    None => Some(wm_state.tree.focused_id)
+                };
+
+                let floating = match job.anonymous_args.first().map(|arg| arg.as_str()) {
+                    Some("on") => Ok(Some(true)),
+                    Some("off") => Ok(Some(false)),
+                    Some("toggle") | None => Ok(None),
+                    _ => Err(String::from("Usage: [@WINDOW] float [on|off|toggle]"))
+                };
+
+                match (element_id, floating) {
+                    (Some(element_id), Ok(floating)) => floating::set_floating(&mut wm_state, element_id, floating),
+                    (None, _) => Err(String::from("That element does not exist in the layout.")),
+                    (_, Err(msg)) => Err(msg)
+                }
+            }
+            else { Err(String::from("Couldn't get a lock on wmstate!")) }
+        },
+        JobType::SCRATCHPAD => {
+            if let Ok(mut wm_state) = WM_STATE.write() {
+                let scratchpad_id = scratchpad::find_scratchpad(&wm_state);
+                let element_id = match job.main_ref {
+                    Some(ref main_ref) => wm_state.tree.tags.address_element(main_ref.clone()).first().cloned(),
+                    None => Some(wm_state.tree.focused_id)
+                };
+
+                // The scratchpad itself stands for the first window hidden in it
+                let element_id = match element_id {
+                    Some(element_id) if Some(element_id) == scratchpad_id => match wm_state.tree.try_lookup_element(element_id)? {
+                        Some(scratchpad) => match scratchpad.profile {
+                            LayoutElementProfile::Padding(ref padding) => padding.floating.first().cloned(),
+                            _ => None
+                        },
+                        None => None
+                    },
+                    element_id => element_id
+                };
+
+                match (element_id, job.anonymous_args.first().map(|arg| arg.as_str())) {
+                    (None, _) => Err(String::from("There is no such window to hide or show.")),
+                    (Some(element_id), Some("hide")) => scratchpad::hide_in_scratchpad(&mut wm_state, element_id),
+                    (Some(element_id), Some("show")) => scratchpad::show_from_scratchpad(&mut wm_state, element_id),
+                    (Some(element_id), Some("toggle")) | (Some(element_id), None) => scratchpad::toggle_scratchpad(&mut wm_state, element_id),
+                    _ => Err(String::from("Usage: [@WINDOW] scratchpad [hide|show|toggle]"))
+                }
+            }
+            else { Err(String::from("Couldn't get a lock on wmstate!")) }
+        },
+        JobType::RULES => {
+            if let Ok(wm_state) = WM_STATE.read() {
+                let element_id = match job.main_ref {
+                    Some(ref main_ref) => wm_state.tree.tags.address_element(main_ref.clone()).first().cloned(),
+                    None => Some(wm_state.tree.focused_id)
+                };
+
+                let view_details = match element_id {
+                    Some(element_id) => match wm_state.tree.try_lookup_element(element_id)? {
+                        Some(element) => match element.profile {
+                            LayoutElementProfile::Window(ref window) => window.get_view().map(|view| (view.pid(), view.app_id().to_string(), view.title().to_string())),
+                            _ => None
+                        },
+                        None => None
+                    },
+                    None => None
+                };
+
+                match (job.anonymous_args.first().map(|arg| arg.as_str()), view_details) {
+                    (Some("test"), Some((pid, app_id, title))) => {
+                        let command = wm_state.launched_commands.get(&pid);
+                        let matched = rules::matching_rules(&wm_state.config.rules, &app_id, &title, command.map(|command| command.as_str()));
+
+                        let mut report = format!("app_id: {}\ntitle: {}\ncommand: {}\n", app_id, title, command.map_or("-", |command| command.as_str()));
+                        if matched.is_empty() {
+                            report.push_str("No rules match.");
+                        }
+                        for (index, rule) in matched {
+                            report.push_str(&format!("Matches rule {}\n", rule.display_name(index)));
+                        }
+
+                        Ok(report)
+                    },
+                    (Some("test"), None) => Err(String::from("Only windows with a view can be tested against the rules.")),
+                    _ => Err(String::from("Usage: [@WINDOW] rules test"))
+                }
+            }
+            else { Err(String::from("Couldn't get a lock on wmstate!")) }
+        },
+        JobType::TAB => {
+            if let Ok(mut wm_state) = WM_STATE.write() {
+                let element_id = match job.main_ref {
+                    Some(ref main_ref) => wm_state.tree.tags.address_element(main_ref.clone()).first().cloned(),
+                    None => Some(wm_state.tree.focused_id)
+                };
+
+                match (element_id, job.anonymous_args.first().map(|arg| arg.as_str())) {
+                    (None, _) => Err(String::from("That element does not exist in the layout.")),
+                    (Some(element_id), Some("next")) => arrangement::switch_tab(&mut wm_state, element_id, Some(1), None),
+                    (Some(element_id), Some("prev")) => arrangement::switch_tab(&mut wm_state, element_id, Some(-1), None),
+                    (Some(element_id), Some("wrap")) => arrangement::tab_element(&mut wm_state, element_id),
+                    (Some(element_id), Some("unwrap")) => arrangement::untab_element(&mut wm_state, element_id),
+                    (Some(element_id), Some(arg)) => match arg.parse::<usize>() {
+                        Ok(index) => arrangement::switch_tab(&mut wm_state, element_id, None, Some(index)),
+                        Err(_) => Err(String::from("Usage: [@WINDOW] tab next|prev|<index>|wrap|unwrap"))
+                    },
+                    (Some(_), None) => Err(String::from("Usage: [@WINDOW] tab next|prev|<index>|wrap|unwrap"))
+                }
+            }
+            else { Err(String::from("Couldn't get a lock on wmstate!")) }
+        },
+        JobType::POLICY => {
+            if let Ok(mut wm_state) = WM_STATE.write() {
+                let workspace_id = match job.main_ref {
+                    Some(ref main_ref) => match wm_state.tree.tags.address_element(main_ref.clone()).first().cloned() {
+                        Some(element_id) => arrangement::workspace_of(&wm_state.tree, element_id)?,
+                        None => None
+                    },
+                    None => wm_state.tree.active_workspace()?.map(|(_, ident)| ident)
+                };
+
+                match (workspace_id, job.anonymous_args.first()) {
+                    (None, _) => Err(String::from("That workspace does not exist in the layout.")),
+                    (Some(workspace_id), None) => Ok(format!("Workspace [{}] is arranged by '{}'.", workspace_id, wm_state.tree.policy_of(workspace_id).name())),
+                    (Some(workspace_id), Some(policy_name)) => match wm_state.config.layout.make_policy(policy_name) {
+                        Some(policy) => arrangement::set_workspace_policy(&mut wm_state, workspace_id, policy),
+                        None => Err(format!("Unknown layout policy: {}", policy_name))
+                    }
+                }
+            }
+            else { Err(String::from("Couldn't get a lock on wmstate!")) }
+        },
+        JobType::LAYOUT_SNAPSHOT => {
+            if let Ok(mut wm_state) = WM_STATE.write() {
+                match (job.anonymous_args.get(0).map(|arg| arg.as_str()), job.anonymous_args.get(1)) {
+                    (Some("save"), Some(name)) => {
+                        let path = LayoutSnapshot::file_path(name)?;
+                        LayoutSnapshot::capture(&wm_state)?.save_to_file(path.clone())?;
+                        Ok(format!("Saved the layout to: {}", path.to_str().unwrap_or(name)))
+                    },
+                    (Some("load"), Some(name)) => {
+                        let snapshot = LayoutSnapshot::load_from_file(LayoutSnapshot::file_path(name)?)?;
+                        snapshot.restore(&mut wm_state, false)
+                    },
+                    (Some("list"), None) => Ok(LayoutSnapshot::saved_names().join("\n")),
+                    _ => Err(String::from("Usage: layout save|load <name> | layout list"))
+                }
+            }
+            else { Err(String::from("Couldn't get a lock on wmstate!")) }
+        },
+        JobType::SESSION => {
+            if let Ok(mut wm_state) = WM_STATE.write() {
+                match job.anonymous_args.first().map(|arg| arg.as_str()) {
+                    Some("save") => {
+                        LayoutSnapshot::capture(&wm_state)?.save_to_file(LayoutSnapshot::session_path())?;
+                        Ok(format!("Saved the session to: {}", LayoutSnapshot::session_path().to_str().unwrap_or("")))
+                    },
+                    Some("restore") => {
+                        let snapshot = LayoutSnapshot::load_from_file(LayoutSnapshot::session_path())?;
+                        snapshot.restore(&mut wm_state, true)
+                    },
+                    _ => Err(String::from("Usage: session save|restore"))
+                }
+            }
+            else { Err(String::from("Couldn't get a lock on wmstate!")) }
+        },
+        JobType::COMPACT => {
+            if let Ok(mut wm_state) = WM_STATE.write() {
+                compaction::compact(&mut wm_state)
+            }
+            else { Err(String::from("Couldn't get a lock on wmstate!")) }
+        },
+        JobType::MASTER => {
+            if let Ok(mut wm_state) = WM_STATE.write() {
+                let element_id = match job.main_ref {
+                    Some(ref main_ref) => wm_state.tree.tags.address_element(main_ref.clone()).first().cloned(),
+                    None => Some(wm_state.tree.focused_id)
+                };
+
+                let workspace_id = match element_id {
+                    Some(element_id) => arrangement::workspace_of(&wm_state.tree, element_id)?.or(wm_state.tree.active_workspace()?.map(|(_, ident)| ident)),
+                    None => None
+                };
+
+                match (element_id, workspace_id) {
+                    (Some(element_id), Some(workspace_id)) => {
+                        let mut layout_policy = wm_state.tree.policy_of(workspace_id);
+                        let result = layout_policy.command(&mut wm_state.tree, workspace_id, element_id, &job.anonymous_args);
+                        wm_state.tree.set_policy_of(workspace_id, layout_policy);
+
+                        LayoutTree::refresh(&mut wm_state);
+                        result
+                    },
+                    _ => Err(String::from("That element does not exist in the layout."))
+                }
+            }
+            else { Err(String::from("Couldn't get a lock on wmstate!")) }
+        },
+        JobType::SUBSCRIBE => {
+            Err(String::from("Subscriptions are handled by the client connection."))
+        },
+        JobType::CONFIG_RELOAD => {
+            if let Ok(mut wm_state) = WM_STATE.write() {
+                // The layout section is left as is since the tree has already been constructed from it
+                let result = match Config::load_from_file(Config::file_path()){
+                    Some(valid_config) => {
+                        wm_state.config.keyboard = valid_config.keyboard;
+                        wm_state.config.background = valid_config.background;
+                        wm_state.config.window = valid_config.window;
+                        wm_state.config.rules = valid_config.rules;
+
+                        if let Some(wallpaper_path) = wm_state.config.background.wallpaper_path.clone(){
+                            wm_state.next_wallpaper_image = Some(schedule_wallpaper_init(PathBuf::from(wallpaper_path)));
+                        }
+
+                        if let Some(rgba) = wm_state.config.background.color_for_gl(){
+                            wm_state.solid_color = Some(SolidColor::new(rgba.0, rgba.1, rgba.2, 1.0f32));
+                        }
+
+                        Ok(String::from("Configuration reloaded."))
+                    },
+                    None => Err(String::from("The configuration file could not be parsed."))
+                };
+
+                broadcast(Event::ConfigReload { success: result.is_ok() });
+                result
+            }
+            else { Err(String::from("Couldn't get a lock on wmstate!")) }
         }
     }
-}
\ No newline at end of file
+}
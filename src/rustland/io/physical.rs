@@ -12,7 +12,10 @@ pub struct InputDevice {
     pub left_click: ButtonState,
     pub right_click: ButtonState,
 
-    pub resize: Option<(LayoutElemID, Orientation)>
+    pub resize: Option<(LayoutElemID, Orientation)>,
+
+    // the floating window being dragged
+    pub drag: Option<LayoutElemID>
 }
 
 impl InputDevice{
@@ -24,7 +27,8 @@ impl InputDevice{
             },
             left_click: ButtonState::Released,
             right_click: ButtonState::Released,
-            resize: None
+            resize: None,
+            drag: None
         }
     }
 
@@ -36,7 +40,8 @@ impl InputDevice{
             },
             left_click: ButtonState::Released,
             right_click: ButtonState::Released,
-            resize: None
+            resize: None,
+            drag: None
         }
     }
 
@@ -0,0 +1,94 @@
+use common::definitions::{SOCKET_FILE_PREFIX, SOCKET_FILE_MODE, SOCKET_DIR_MODE};
+
+use std::env;
+use std::fs::{self, Permissions, DirBuilder};
+use std::os::unix::fs::{PermissionsExt, DirBuilderExt};
+use std::os::unix::net::UnixListener;
+use std::path::PathBuf;
+use std::process;
+use std::sync::Mutex;
+
+lazy_static! {
+    pub static ref UNIX_SOCKET: Option<Mutex<UnixListener>> = 
+        match bind_session_socket() {
+            Some(working_listener) => Some(Mutex::new(working_listener)),
+            None => None
+        };
+}
+
+/// The directory of the socket of this session, i.e. $XDG_RUNTIME_DIR/rustland.<pid>
+fn session_socket_dir() -> Option<PathBuf> {
+    match env::var_os("XDG_RUNTIME_DIR") {
+        Some(runtime_dir) => {
+            let mut socket_dir = PathBuf::from(runtime_dir);
+            socket_dir.push(format!("{}.{}", SOCKET_FILE_PREFIX, process::id()));
+
+            Some(socket_dir)
+        },
+        None => {
+            println!("XDG_RUNTIME_DIR is not set - the IPC socket will not be available.");
+            None
+        }
+    }
+}
+
+/// The socket path of this session, i.e. $XDG_RUNTIME_DIR/rustland.<pid>/rustland.sock
+pub fn session_socket_path() -> Option<PathBuf> {
+    session_socket_dir().map(|mut socket_path| {
+        socket_path.push(format!("{}.sock", SOCKET_FILE_PREFIX));
+        socket_path
+    })
+}
+
+/// The socket path of this session, if the socket could be bound
+pub fn bound_socket_path() -> Option<PathBuf> {
+    match *UNIX_SOCKET {
+        Some(_) => session_socket_path(),
+        None => None
+    }
+}
+
+fn bind_session_socket() -> Option<UnixListener> {
+    let (socket_dir, socket_path) = match (session_socket_dir(), session_socket_path()) {
+        (Some(dir), Some(path)) => (dir, path),
+        _ => return None
+    };
+
+    // A leftover from a crashed session with the same pid would make the bind fail
+    if socket_dir.exists() {
+        fs::remove_dir_all(&socket_dir);
+    }
+
+    // Nobody but the owner can reach the socket through this directory, not even before its own permissions are set
+    if let Err(e) = DirBuilder::new().mode(SOCKET_DIR_MODE).create(&socket_dir) {
+        println!("Couldn't create the socket directory {}: {}", socket_dir.to_str().unwrap_or("?"), e);
+        return None;
+    }
+
+    match UnixListener::bind(&socket_path) {
+        Ok(listener) => {
+            // Only the owner of the session is allowed to drive the compositor
+            if let Err(e) = fs::set_permissions(&socket_path, Permissions::from_mode(SOCKET_FILE_MODE)) {
+                println!("Couldn't restrict permissions of {}: {}", socket_path.to_str().unwrap_or("?"), e);
+                fs::remove_dir_all(&socket_dir);
+                return None;
+            }
+
+            println!("Listening for IPC clients at: {}", socket_path.to_str().unwrap_or("?"));
+            Some(listener)
+        },
+        Err(e) => {
+            println!("Couldn't bind IPC socket at {}: {}", socket_path.to_str().unwrap_or("?"), e);
+            fs::remove_dir_all(&socket_dir);
+            None
+        }
+    }
+}
+
+pub fn remove_session_socket() {
+    if let Some(socket_dir) = session_socket_dir() {
+        if socket_dir.exists() {
+            fs::remove_dir_all(socket_dir);
+        }
+    }
+}
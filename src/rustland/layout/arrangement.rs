@@ -3,17 +3,27 @@ use std::cell::RefMut;
 use std::fmt;
 
 use common::definitions::{TAG_PREFIX, PROPERTY_PREFIX, LayoutElemID};
-use layout::LayoutTree;
+use common::event::Event;
+use io::event::broadcast;
+use layout::{LayoutTree, PARENT_ELEMENT};
 use layout::element::{LayoutElement, LayoutElementProfile};
-use layout::element::bisect::Side;
+use layout::element::grid::{Grid, GridAxis, Direction};
+use layout::element::tabbed::Tabbed;
+use layout::element::split::Split;
+use layout::element::window::WindowMode;
+use layout::element::bisect::{Bisect, Orientation, Side, MIN_RATIO, MAX_RATIO};
+use layout::policy::LayoutPolicy;
 use layout::arrangement;
+use layout::floating::is_floating;
+use layout::navigation::focus_window;
 use wmstate::*;
 use utils::geometry::GeometryExt;
 use sugars::Renderable;
 use sugars::program::GraphicsProgram;
 
+use serde_json::{Value, Map};
 
-use wlc::{Size, Geometry, Visibility};
+use wlc::{Geometry, Visibility};
 
 /// Arrangement  
 /// Recursive methods for describing and interacting with the layout
@@ -59,8 +69,17 @@ pub fn tree(tree: &LayoutTree, f: &mut fmt::Formatter, outer_element_id: LayoutE
         output
     };
 
+    let outer_element = match tree.try_lookup_element(outer_element_id) {
+        Ok(outer_element) => outer_element,
+        Err(msg) => {
+            indent(*indentation_whtspcs, f);
+            writeln!(f, "├──[{}] {}", outer_element_id, msg);
+            return;
+        }
+    };
+
     // Use debug for LayoutElement
-    if let Some(mut outer_element) = tree.lookup_element(outer_element_id){
+    if let Some(mut outer_element) = outer_element{
         let tags = format_tags(outer_element_id);
         let props = format_props(outer_element_id, &mut outer_element);
 
@@ -77,6 +96,17 @@ pub fn tree(tree: &LayoutTree, f: &mut fmt::Formatter, outer_element_id: LayoutE
                 }
                 *indentation_whtspcs -= 1;
             },
+            LayoutElementProfile::Split(ref split) => {
+                indent(*indentation_whtspcs, f);
+                writeln!(f, "├──[{}] Split {:?}: {} {}", outer_element_id, split.weights(), tags, props);
+
+                *indentation_whtspcs += 1;
+                for child_id in split.children_iter() {
+                    //Recursion
+                    arrangement::tree(tree, f, *child_id, indentation_whtspcs);
+                }
+                *indentation_whtspcs -= 1;
+            },
             LayoutElementProfile::Grid(ref element) => {
                 for (i, child_id) in element.get_all_children().iter().enumerate(){
                     indent(*indentation_whtspcs, f);
@@ -98,6 +128,17 @@ pub fn tree(tree: &LayoutTree, f: &mut fmt::Formatter, outer_element_id: LayoutE
 
                 writeln!(f);
             },
+            LayoutElementProfile::Tabbed(ref tabbed) => {
+                indent(*indentation_whtspcs, f);
+                writeln!(f, "├──[{}] Tabbed (tab {} of {}): {} {}", outer_element_id, tabbed.active_tab() + 1, tabbed.tab_count(), tags, props);
+
+                *indentation_whtspcs += 1;
+                for child_id in tabbed.children_iter() {
+                    //Recursion
+                    arrangement::tree(tree, f, *child_id, indentation_whtspcs);
+                }
+                *indentation_whtspcs -= 1;
+            },
             LayoutElementProfile::Padding(ref padding) => {
                 indent(*indentation_whtspcs, f);
                 writeln!(f, "├──[{}] Padding: {} {}", outer_element_id, tags, props);
@@ -107,6 +148,11 @@ pub fn tree(tree: &LayoutTree, f: &mut fmt::Formatter, outer_element_id: LayoutE
                 //Recursion                    
                 arrangement::tree(tree, f, padding.child_elem_id, indentation_whtspcs);
 
+                for floating_id in padding.floating.iter() {
+                    //Recursion
+                    arrangement::tree(tree, f, *floating_id, indentation_whtspcs);
+                }
+
                 *indentation_whtspcs -= 1;
             },
             LayoutElementProfile::Window(ref window) => {
@@ -126,58 +172,245 @@ pub fn tree(tree: &LayoutTree, f: &mut fmt::Formatter, outer_element_id: LayoutE
     }
 }
 
-pub fn find_first_unoccupied(tree: &LayoutTree, outer_element_id: LayoutElemID) -> Option<LayoutElemID>{
-    if let Some(ref mut outer_element) =  tree.lookup_element(outer_element_id){
-        let bisect_ratio = outer_element.get_property("ratio");
+pub fn tree_json(tree: &LayoutTree, outer_element_id: LayoutElemID) -> Result<Value, String> {
+    // Computed up front as the traversal needs to borrow this element
+    let geometry = tree.geometry_of(outer_element_id)?;
+    let tags = tree.tags.address_tags_by_element(outer_element_id);
+
+    let (mut node, child_ids) = match tree.try_lookup_element(outer_element_id)? {
+        Some(mut outer_element) => {
+            let mut properties = Map::new();
+            let mut property_names = outer_element.properties.get_all_property_names();
+            property_names.sort();
+
+            for property_name in property_names{
+                if let Some(property_value) = outer_element.get_property(property_name) {
+                    properties.insert(property_name.to_string(), json!(property_value));
+                }
+            }
+
+            let mut node = json!({
+                "id": outer_element_id,
+                "type": if outer_element.profile.is_none() { String::from("unoccupied") } else { format!("{}", outer_element.profile) },
+                "tags": tags,
+                "properties": properties,
+                "geometry": geometry.map(|g| json!({ "x": g.origin.x, "y": g.origin.y, "w": g.size.w, "h": g.size.h }))
+            });
+
+            let child_ids = match outer_element.profile {
+                LayoutElementProfile::Bisect(ref bisect) => bisect.children_iter().cloned().collect(),
+                LayoutElementProfile::Split(ref split) => {
+                    node["weights"] = json!(split.weights());
+                    split.get_all_children().clone()
+                },
+                LayoutElementProfile::Grid(ref grid) => {
+                    node["active_workspace"] = json!(grid.active_subspace());
+                    grid.get_all_children().clone()
+                },
+                LayoutElementProfile::Tabbed(ref tabbed) => {
+                    node["active_tab"] = json!(tabbed.active_tab());
+                    tabbed.get_all_children().clone()
+                },
+                LayoutElementProfile::Padding(ref padding) => outer_element.profile.children(),
+                LayoutElementProfile::Window(ref window) => {
+                    node["view"] = match window.get_view() {
+                        Some(view) => json!({ "pid": view.pid(), "title": view.title().to_string(), "app_id": view.app_id().to_string() }),
+                        None => Value::Null
+                    };
+                    Vec::new()
+                },
+                LayoutElementProfile::None => Vec::new()
+            };
+
+            (node, child_ids)
+        },
+        None => return Ok(Value::Null)
+    };
 
+    if !child_ids.is_empty() {
+        let mut children = Vec::new();
+        for child_id in child_ids {
+            // Recursion
+            children.push(tree_json(tree, child_id)?);
+        }
+
+        node["children"] = Value::Array(children);
+    }
+
+    Ok(node)
+}
+
+/// Finds an unoccupied place without changing a thing - see reveal_place for bringing it into view.
+/// Places reserved for certain windows are passed over.
+pub fn find_first_unoccupied(tree: &LayoutTree, outer_element_id: LayoutElemID) -> Result<Option<LayoutElemID>, String>{
+    if let Some(ref outer_element) =  tree.try_lookup_element(outer_element_id)?{
         match outer_element.profile {
-            LayoutElementProfile::None => {
-                return Some(outer_element_id);
+            LayoutElementProfile::None if !tree.is_reserved(outer_element_id) => {
+                return Ok(Some(outer_element_id));
             },
             LayoutElementProfile::Padding(ref padding) => {
                 // Recursion to another layer of depth in the tree structure
-                if let Some(candidate_id) = find_first_unoccupied(tree, padding.child_elem_id){
-                    return Some(candidate_id);
+                if let Some(candidate_id) = find_first_unoccupied(tree, padding.child_elem_id)?{
+                    return Ok(Some(candidate_id));
                 }
             },
             LayoutElementProfile::Bisect(ref bisect) =>{
-                let mut iter = bisect.children_iter();
-
-                while let Some(&child_ident) = iter.next(){
-                    if let Some(element) = tree.lookup_element(child_ident){
-                        if element.profile.is_none() {
-                            tree.animate_property_explicitly(outer_element_id, "ratio", bisect_ratio.unwrap(), 0.5f32, false, 125, 0);    
-                            return Some(child_ident);
-                        }
+                for &child_ident in bisect.children_iter() {
+                    if is_unoccupied(tree, child_ident)? {
+                        return Ok(Some(child_ident));
                     }
                 }
 
                 for element_id in bisect.children_iter() {
                     // Recursion to another layer of depth in the tree structure
-                    if let Some(candidate_id) = find_first_unoccupied(tree, *element_id){
-                        return Some(candidate_id);
+                    if let Some(candidate_id) = find_first_unoccupied(tree, *element_id)?{
+                        return Ok(Some(candidate_id));
                     }   
                 }
             },
+            LayoutElementProfile::Split(ref split) => {
+                for &child_ident in split.children_iter() {
+                    if is_unoccupied(tree, child_ident)? {
+                        return Ok(Some(child_ident));
+                    }
+                }
+
+                for element_id in split.children_iter() {
+                    // Recursion to another layer of depth in the tree structure
+                    if let Some(candidate_id) = find_first_unoccupied(tree, *element_id)?{
+                        return Ok(Some(candidate_id));
+                    }
+                }
+            },
             LayoutElementProfile::Grid(ref wrkspc) => {
                 // Recursion to another layer of depth in the tree structure
-                if let Some(candidate_id) = find_first_unoccupied(tree, wrkspc.get_active_child_id()){
-                    return Some(candidate_id);
+                if let Some(candidate_id) = find_first_unoccupied(tree, wrkspc.get_active_child_id())?{
+                    return Ok(Some(candidate_id));
+                }
+            },
+            LayoutElementProfile::Tabbed(ref tabbed) => {
+                for &child_ident in tabbed.children_iter() {
+                    if is_unoccupied(tree, child_ident)? {
+                        return Ok(Some(child_ident));
+                    }
+                }
+
+                if let Some(active_id) = tabbed.get_active_child_id() {
+                    // Recursion to another layer of depth in the tree structure
+                    if let Some(candidate_id) = find_first_unoccupied(tree, active_id)?{
+                        return Ok(Some(candidate_id));
+                    }
                 }
             },
             _ => {}
         }
     }
-    return None;
+    return Ok(None);
 }
 
-pub fn arrange(tree: &LayoutTree, outer_element_id: LayoutElemID, outer_geometry: Geometry, stacked_padding: &mut Option<u32>, stacked_scale: &mut (f32, f32), program: Option<&GraphicsProgram>) {
-    if let Some(mut outer_element) = tree.lookup_element(outer_element_id){
+fn is_unoccupied(tree: &LayoutTree, element_ident: LayoutElemID) -> Result<bool, String> {
+    if tree.is_reserved(element_ident) {
+        return Ok(false);
+    }
+
+    Ok(tree.try_lookup_element(element_ident)?.map_or(false, |element| element.profile.is_none()))
+}
+
+/// The unoccupied places beneath an element that are reserved for certain windows
+pub fn find_reserved_places(matches: &mut Vec<LayoutElemID>, tree: &LayoutTree, outer_element_id: LayoutElemID) -> Result<(), String> {
+    let child_ids = match tree.try_lookup_element(outer_element_id)? {
+        Some(outer_element) => match outer_element.profile {
+            LayoutElementProfile::None => {
+                if tree.is_reserved(outer_element_id) {
+                    matches.push(outer_element_id);
+                }
+
+                return Ok(());
+            },
+            LayoutElementProfile::Padding(ref padding) => vec![padding.child_elem_id],
+            ref profile => profile.children()
+        },
+        None => return Ok(())
+    };
+
+    for child_ident in child_ids {
+        // Recursion
+        find_reserved_places(matches, tree, child_ident)?;
+    }
+
+    Ok(())
+}
+
+/// Brings a place into view once something is seated in it - a tab is brought to the front, a collapsed bisect is opened up again
+pub fn reveal_place(tree: &LayoutTree, element_ident: LayoutElemID) -> Result<(), String> {
+    let parent_ident = match tree.try_lookup_element(element_ident)?.and_then(|element| element.parent_id) {
+        Some(parent_ident) => parent_ident,
+        None => return Ok(())
+    };
+
+    let collapsed_ratio = match tree.try_lookup_element(parent_ident)? {
+        Some(mut parent) => {
+            let ratio = parent.get_property("ratio");
+
+            match parent.profile {
+                LayoutElementProfile::Tabbed(ref mut tabbed) => {
+                    tabbed.activate_child(element_ident);
+                    None
+                },
+                // Only a collapsed bisect is opened up again - a ratio of its own is kept
+                LayoutElementProfile::Bisect(_) => match ratio {
+                    Some(ratio) if ratio < MIN_RATIO || ratio > MAX_RATIO => Some(ratio),
+                    _ => None
+                },
+                _ => None
+            }
+        },
+        None => None
+    };
+
+    // The parent is no longer borrowed once its ratio is animated
+    if let Some(ratio) = collapsed_ratio {
+        tree.animate_property_explicitly(parent_ident, "ratio", ratio, 0.5f32, false, 125, 0);
+    }
+
+    Ok(())
+}
+
+pub fn find_grid(tree: &LayoutTree, outer_element_id: LayoutElemID) -> Result<Option<LayoutElemID>, String>{
+    let child_ids: Vec<LayoutElemID> = match tree.try_lookup_element(outer_element_id)? {
+        Some(outer_element) => match outer_element.profile {
+            LayoutElementProfile::Grid(_) => return Ok(Some(outer_element_id)),
+            LayoutElementProfile::Padding(ref padding) => vec![padding.child_elem_id],
+            LayoutElementProfile::Bisect(ref bisect) => bisect.children_iter().cloned().collect(),
+            LayoutElementProfile::Split(ref split) => split.get_all_children().clone(),
+            _ => Vec::new()
+        },
+        None => Vec::new()
+    };
+
+    for child_id in child_ids {
+        // Recursion to another layer of depth in the tree structure
+        if let Some(grid_id) = find_grid(tree, child_id)? {
+            return Ok(Some(grid_id));
+        }
+    }
+
+    Ok(None)
+}
+
+pub fn arrange(tree: &LayoutTree, outer_element_id: LayoutElemID, outer_geometry: Geometry, stacked_padding: &mut Option<u32>, stacked_scale: &mut (f32, f32), program: Option<&GraphicsProgram>) -> Result<(), String> {
+    if let Some(mut outer_element) = tree.try_lookup_element(outer_element_id)?{
         match outer_element.profile{
             LayoutElementProfile::Bisect(ref bisect) => {               
                 for (i, child_id) in bisect.children_iter().enumerate() {   
                     // Recursion
-                    arrange(tree, *child_id, bisect.get_offset_geometry(outer_geometry, stacked_padding, i as i32), stacked_padding, stacked_scale, program);
+                    arrange(tree, *child_id, bisect.get_offset_geometry(outer_geometry, stacked_padding, i as i32), stacked_padding, stacked_scale, program)?;
+                }
+            },
+            LayoutElementProfile::Split(ref split) => {
+                for (i, child_id) in split.children_iter().enumerate() {
+                    // Recursion
+                    arrange(tree, *child_id, split.get_offset_geometry(outer_geometry, stacked_padding, i), stacked_padding, stacked_scale, program)?;
                 }
             },
             LayoutElementProfile::Grid(ref mut grid) =>  { 
@@ -185,7 +418,17 @@ pub fn arrange(tree: &LayoutTree, outer_element_id: LayoutElemID, outer_geometry
                     // Recursion
                     let offset_geometry = grid.get_offset_geometry(tree.get_outer_geometry(), outer_geometry, i as u16, stacked_scale); 
 
-                    arrange(tree, *child_id, offset_geometry, stacked_padding, stacked_scale, program);
+                    arrange(tree, *child_id, offset_geometry, stacked_padding, stacked_scale, program)?;
+                }
+            },
+            LayoutElementProfile::Tabbed(ref mut tabbed) => {
+                if let Some(prog) = program{
+                    tabbed.draw(prog, outer_geometry);
+                }
+
+                for (i, child_id) in tabbed.children_iter().enumerate() {
+                    // Recursion
+                    arrange(tree, *child_id, tabbed.get_offset_geometry(tree.get_outer_geometry(), outer_geometry, i), stacked_padding, stacked_scale, program)?;
                 }
             },
             LayoutElementProfile::Padding(ref mut padding) => {
@@ -198,13 +441,21 @@ pub fn arrange(tree: &LayoutTree, outer_element_id: LayoutElemID, outer_geometry
                 }
 
                 // Recursion
-                arrange(tree, padding.child_elem_id, padding.get_offset_geometry(outer_geometry, stacked_scale), stacked_padding, stacked_scale, program);
+                arrange(tree, padding.child_elem_id, padding.get_offset_geometry(outer_geometry, stacked_scale), stacked_padding, stacked_scale, program)?;
+
+                // The floating layer is placed relative to the padding itself, above the child
+                for floating_id in padding.floating.iter() {
+                    arrange(tree, *floating_id, outer_geometry, stacked_padding, stacked_scale, program)?;
+                }
 
                 (*stacked_scale).0 /= if padding.inner_scale_x != 0f32 { padding.inner_scale_x } else { panic!("X-scaling can't be 0.") };
                 (*stacked_scale).0 /= if padding.inner_scale_y != 0f32 { padding.inner_scale_y } else { panic!("Y-scaling can't be 0.") };
                 *stacked_padding = None;
             },
             LayoutElementProfile::Window(ref mut window) => {
+                let is_floating = window.floating.is_some();
+                let outer_geometry = window.get_floating_geometry(outer_geometry, stacked_scale).unwrap_or(outer_geometry);
+
                 let visibility = match tree.outer_geometry.overlaps_geometry(outer_geometry) { 
                     true => Visibility::Slot1,
                     false => Visibility::Null 
@@ -212,39 +463,63 @@ pub fn arrange(tree: &LayoutTree, outer_element_id: LayoutElemID, outer_geometry
 
                 if let Some(view) = window.get_view(){
                     view.set_visibility(visibility);
+
+                    if is_floating {
+                        view.bring_to_front();
+                    }
                 }
 
                 if visibility != Visibility::Null  {
-                    window.set_desired_geometry(outer_geometry.clone());
+                    window.set_desired_geometry(window.get_offset_geometry(outer_geometry));
                 }
             },
             _ => {}
         }  
     }
+
+    Ok(())
 }
 
 
 
-pub fn geometry_of(tree: &LayoutTree, outer_element_id: LayoutElemID, target_element_id: LayoutElemID, outer_geometry: Geometry, stacked_padding: &mut Option<u32>, stacked_scale: &mut (f32, f32)) -> Option<Geometry> {
-    if outer_element_id == target_element_id { return Some(outer_geometry); } 
+pub fn geometry_of(tree: &LayoutTree, outer_element_id: LayoutElemID, target_element_id: LayoutElemID, outer_geometry: Geometry, stacked_padding: &mut Option<u32>, stacked_scale: &mut (f32, f32)) -> Result<Option<Geometry>, String> {
+    if outer_element_id == target_element_id { return Ok(Some(outer_geometry)); } 
 
-    if let Some(mut outer_element) = tree.lookup_element(outer_element_id){
+    if let Some(mut outer_element) = tree.try_lookup_element(outer_element_id)?{
         match outer_element.profile{
             LayoutElementProfile::Bisect(ref bisect) => {               
                 for (i, child_id) in bisect.children_iter().enumerate() {   
                     // Recursion
-                    let rec = geometry_of(tree, *child_id, target_element_id, bisect.get_offset_geometry(outer_geometry, stacked_padding, i as i32), stacked_padding, stacked_scale);
+                    let rec = geometry_of(tree, *child_id, target_element_id, bisect.get_offset_geometry(outer_geometry, stacked_padding, i as i32), stacked_padding, stacked_scale)?;
                     if rec.is_some() {
-                        return rec;
+                        return Ok(rec);
+                    }
+                }
+            },
+            LayoutElementProfile::Split(ref split) => {
+                for (i, child_id) in split.children_iter().enumerate() {
+                    // Recursion
+                    let rec = geometry_of(tree, *child_id, target_element_id, split.get_offset_geometry(outer_geometry, stacked_padding, i), stacked_padding, stacked_scale)?;
+                    if rec.is_some() {
+                        return Ok(rec);
                     }
                 }
             },
             LayoutElementProfile::Grid(ref mut grid) =>  { 
                 for (i, child_id) in grid.children_iter().enumerate() {   
                     // Recursion
-                    let rec = geometry_of(tree, *child_id, target_element_id, grid.get_offset_geometry(tree.get_outer_geometry(), outer_geometry, i as u16, stacked_scale), stacked_padding, stacked_scale);
+                    let rec = geometry_of(tree, *child_id, target_element_id, grid.get_offset_geometry(tree.get_outer_geometry(), outer_geometry, i as u16, stacked_scale), stacked_padding, stacked_scale)?;
                     if rec.is_some() {
-                        return rec;
+                        return Ok(rec);
+                    }
+                }
+            },
+            LayoutElementProfile::Tabbed(ref tabbed) => {
+                for (i, child_id) in tabbed.children_iter().enumerate() {
+                    // Recursion
+                    let rec = geometry_of(tree, *child_id, target_element_id, tabbed.get_offset_geometry(tree.get_outer_geometry(), outer_geometry, i), stacked_padding, stacked_scale)?;
+                    if rec.is_some() {
+                        return Ok(rec);
                     }
                 }
             },
@@ -254,9 +529,19 @@ pub fn geometry_of(tree: &LayoutTree, outer_element_id: LayoutElemID, target_ele
                 *stacked_padding = Some(padding.gap_size);
 
                 // Recursion
-                let rec = geometry_of(tree, padding.child_elem_id, target_element_id, padding.get_offset_geometry(outer_geometry, stacked_scale), stacked_padding, stacked_scale);
+                let rec = geometry_of(tree, padding.child_elem_id, target_element_id, padding.get_offset_geometry(outer_geometry, stacked_scale), stacked_padding, stacked_scale)?;
                 if rec.is_some() {
-                    return rec;
+                    return Ok(rec);
+                }
+
+                if padding.floating.contains(&target_element_id) {
+                    return match tree.try_lookup_element(target_element_id)? {
+                        Some(target) => match target.profile {
+                            LayoutElementProfile::Window(ref window) => Ok(window.get_floating_geometry(outer_geometry, stacked_scale)),
+                            _ => Ok(None)
+                        },
+                        None => Ok(None)
+                    };
                 }
 
                 (*stacked_scale).0 /= if padding.inner_scale_x != 0f32 { padding.inner_scale_x } else { panic!("X-scaling can't be 0.") };
@@ -267,57 +552,1062 @@ pub fn geometry_of(tree: &LayoutTree, outer_element_id: LayoutElemID, target_ele
         }  
     }
 
-    return None;
+    return Ok(None);
 }
 
 
-pub fn find_all_windows(matches: &mut Vec<LayoutElemID>, needs_to_be_active: bool, tree: &LayoutTree, outer_element_id: LayoutElemID) {
-    if let Some(ref mut outer_element) =  tree.lookup_element(outer_element_id){
+pub fn find_all_windows(matches: &mut Vec<LayoutElemID>, needs_to_be_active: bool, tree: &LayoutTree, outer_element_id: LayoutElemID) -> Result<(), String> {
+    if let Some(ref mut outer_element) =  tree.try_lookup_element(outer_element_id)?{
         match outer_element.profile {
             LayoutElementProfile::Padding(ref padding) => {
                 // Recursion to another layer of depth in the tree structure
-                find_all_windows(matches, needs_to_be_active, tree, padding.child_elem_id);
+                find_all_windows(matches, needs_to_be_active, tree, padding.child_elem_id)?;
+
+                for floating_id in padding.floating.iter() {
+                    find_all_windows(matches, needs_to_be_active, tree, *floating_id)?;
+                }
             },
             LayoutElementProfile::Bisect(ref bisect) =>{
                 for candidate_id in bisect.children_iter() {
                     // Recursion to another layer of depth in the tree structure
-                    find_all_windows(matches, needs_to_be_active, tree, *candidate_id);
+                    find_all_windows(matches, needs_to_be_active, tree, *candidate_id)?;
+                }
+            },
+            LayoutElementProfile::Split(ref split) => {
+                for candidate_id in split.children_iter() {
+                    // Recursion to another layer of depth in the tree structure
+                    find_all_windows(matches, needs_to_be_active, tree, *candidate_id)?;
                 }
             },
             LayoutElementProfile::Grid(ref wrkspc) => {
                 // Recursion to another layer of depth in the tree structure
                 if needs_to_be_active {
-                    find_all_windows(matches, needs_to_be_active, tree, wrkspc.get_active_child_id());
+                    find_all_windows(matches, needs_to_be_active, tree, wrkspc.get_active_child_id())?;
                 }
                 else {
                     for candidate_id in wrkspc.get_all_children().iter() {
-                        find_all_windows(matches, needs_to_be_active, tree, *candidate_id);
+                        find_all_windows(matches, needs_to_be_active, tree, *candidate_id)?;
                     }
                 }
 
             },
+            LayoutElementProfile::Tabbed(ref tabbed) => {
+                // Recursion to another layer of depth in the tree structure
+                if needs_to_be_active {
+                    if let Some(active_id) = tabbed.get_active_child_id() {
+                        find_all_windows(matches, needs_to_be_active, tree, active_id)?;
+                    }
+                }
+                else {
+                    for candidate_id in tabbed.children_iter() {
+                        find_all_windows(matches, needs_to_be_active, tree, *candidate_id)?;
+                    }
+                }
+            },
             LayoutElementProfile::Window(_) => {
                 matches.push(outer_element_id);
             },
             _ => {}
         }
     }
+
+    Ok(())
+}
+
+/// The workspace (i.e. a child of the grid) that an element belongs to
+pub fn workspace_of(tree: &LayoutTree, element_ident: LayoutElemID) -> Result<Option<LayoutElemID>, String> {
+    let mut current_ident = element_ident;
+
+    loop {
+        let parent_ident = match tree.try_lookup_element(current_ident)?.and_then(|element| element.parent_id) {
+            Some(parent_ident) => parent_ident,
+            None => return Ok(None)
+        };
+
+        let parent_is_grid = match tree.try_lookup_element(parent_ident)? {
+            Some(parent) => match parent.profile { LayoutElementProfile::Grid(_) => true, _ => false },
+            None => false
+        };
+
+        if parent_is_grid {
+            return Ok(Some(current_ident));
+        }
+
+        current_ident = parent_ident;
+    }
 }
 
-pub fn move_element(wm_state: &mut WMState, carry: LayoutElemID, destination: LayoutElemID) -> Result<String, String>{
-    if let Some(mut destination) = wm_state.tree.lookup_element(destination){
-        match destination.profile {
+/// Takes an element (and everything beneath it) out of its container without discarding it.
+/// A bisect left with a single child collapses into that child, a padding gets an unoccupied slot.
+pub fn detach_element(tree: &mut LayoutTree, element_ident: LayoutElemID) -> Result<(), String> {
+    let parent_ident = match tree.try_lookup_element(element_ident)? {
+        Some(element) => match element.parent_id {
+            Some(parent_ident) => parent_ident,
+            None => return Err(String::from("The element is not attached to the layout."))
+        },
+        None => return Err(String::from("Element missing in layout."))
+    };
+
+    // Leaving the floating layer leaves no gap behind
+    let left_floating_layer = match tree.try_lookup_element(parent_ident)? {
+        Some(mut parent) => match parent.profile {
+            LayoutElementProfile::Padding(ref mut padding) if padding.floating.contains(&element_ident) => {
+                padding.floating.retain(|&e| e != element_ident);
+                true
+            },
+            _ => false
+        },
+        None => false
+    };
+
+    // Leaving one of several tabs only closes that tab, the same goes for a split of more than two
+    let left_siblings = match tree.try_lookup_element(parent_ident)? {
+        Some(mut parent) => match parent.profile {
+            LayoutElementProfile::Tabbed(ref mut tabbed) if tabbed.tab_count() > 1 => {
+                tabbed.disown_child(element_ident);
+                true
+            },
+            LayoutElementProfile::Split(ref mut split) if split.child_count() > 2 => {
+                split.disown_child(element_ident);
+                true
+            },
+            _ => false
+        },
+        None => false
+    };
+
+    if left_floating_layer || left_siblings {
+        tree.set_parent(element_ident, None)?;
+        return Ok(());
+    }
+
+    let sibling_ident = match tree.try_lookup_element(parent_ident)? {
+        Some(parent) => match parent.profile {
+            LayoutElementProfile::Bisect(ref bisect) => bisect.sibling_of(element_ident),
+            LayoutElementProfile::Split(ref split) => split.children_iter().cloned().find(|&e| e != element_ident),
+            LayoutElementProfile::Padding(_) | LayoutElementProfile::Tabbed(_) => None,
+            LayoutElementProfile::Grid(_) => return Err(String::from("Workspaces can't be detached from the grid.")),
+            _ => return Err(String::from("The parent of the element is not a container."))
+        },
+        None => return Err(String::from("Parent element missing in layout."))
+    };
+
+    match sibling_ident {
+        Some(sibling_ident) => {
+            // The remaining child takes over the place of the bisect (or split):
+            let grand_parent_ident = tree.parent_of(parent_ident)?;
+
+            if let Some(mut grand_parent) = tree.try_lookup_element(grand_parent_ident)? {
+                grand_parent.profile.replace_child(parent_ident, sibling_ident);
+            }
+
+            tree.set_parent(sibling_ident, Some(grand_parent_ident))?;
+            tree.discard_element(parent_ident)?;
+        },
+        None => {
+            let placeholder_ident = tree.spawn_dummy_element(Some(parent_ident))?;
+
+            if let Some(mut parent) = tree.try_lookup_element(parent_ident)? {
+                parent.profile.replace_child(element_ident, placeholder_ident);
+            }
+        }
+    }
+
+    tree.set_parent(element_ident, None)?;
+    Ok(())
+}
+
+/// Puts a detached element in the place of an unoccupied slot
+pub fn seat_element_in_slot(tree: &mut LayoutTree, element_ident: LayoutElemID, slot_ident: LayoutElemID) -> Result<(), String> {
+    let parent_ident = tree.parent_of(slot_ident)?;
+
+    if let Some(mut parent) = tree.try_lookup_element(parent_ident)? {
+        parent.profile.replace_child(slot_ident, element_ident);
+    }
+
+    tree.set_parent(element_ident, Some(parent_ident))?;
+    tree.discard_element(slot_ident)?;
+
+    Ok(())
+}
+
+/// Splits the place of 'target_ident' in two and puts a detached element next to it
+pub fn seat_element_beside(tree: &mut LayoutTree, element_ident: LayoutElemID, target_ident: LayoutElemID) -> Result<(), String> {
+    let parent_ident = tree.parent_of(target_ident)?;
+    // Only a preference, so a place without a geometry of its own is split side by side
+    let orientation = match tree.geometry_of(target_ident)? {
+        Some(geometry) if geometry.size.h > geometry.size.w => Orientation::Vertical,
+        _ => Orientation::Horizontal
+    };
+
+    let (bisect_ident, mut bisect) = Bisect::init(tree.spawn_dummy_element(Some(parent_ident))?, tree, orientation, 0.5f32)?;
+    let (former_slot, latter_slot) = {
+        let mut iter = bisect.children_iter().cloned();
+
+        (iter.next().unwrap(), iter.next().unwrap())
+    };
+
+    bisect.replace_child(former_slot, target_ident);
+    bisect.replace_child(latter_slot, element_ident);
+    tree.discard_element(former_slot)?;
+    tree.discard_element(latter_slot)?;
+
+    if let Some(mut parent) = tree.try_lookup_element(parent_ident)? {
+        parent.profile.replace_child(target_ident, bisect_ident);
+    }
+
+    tree.reserve_element_identity(bisect_ident, LayoutElementProfile::Bisect(bisect))?;
+    tree.set_parent(target_ident, Some(bisect_ident))?;
+    tree.set_parent(element_ident, Some(bisect_ident))?;
+
+    Ok(())
+}
+
+/// The nearest ancestor bisect whose split is the edge of 'element_ident' facing 'direction'
+pub fn find_resizable_bisect(tree: &LayoutTree, element_ident: LayoutElemID, direction: Direction) -> Result<Option<LayoutElemID>, String> {
+    let (orientation, child_index) = match direction {
+        Direction::LEFT => (Orientation::Horizontal, 1),
+        Direction::RIGHT => (Orientation::Horizontal, 0),
+        Direction::UP => (Orientation::Vertical, 1),
+        Direction::DOWN => (Orientation::Vertical, 0)
+    };
+
+    let mut child_ident = element_ident;
+
+    while let Some(parent_ident) = tree.try_lookup_element(child_ident)?.and_then(|element| element.parent_id) {
+        if let Some(parent) = tree.try_lookup_element(parent_ident)? {
+            match parent.profile {
+                LayoutElementProfile::Bisect(ref bisect) => {
+                    if bisect.orientation == orientation && bisect.child_index(child_ident) == Some(child_index) {
+                        return Ok(Some(parent_ident));
+                    }
+                },
+                LayoutElementProfile::Split(ref split) => {
+                    // Any child with a neighbour on that side has an edge to move
+                    let has_neighbour = match split.child_index(child_ident) {
+                        Some(index) if child_index == 0 => index + 1 < split.child_count(),
+                        Some(index) => index > 0,
+                        None => false
+                    };
+
+                    if split.orientation == orientation && has_neighbour {
+                        return Ok(Some(parent_ident));
+                    }
+                },
+                _ => {}
+            }
+        }
+
+        child_ident = parent_ident;
+    }
+
+    Ok(None)
+}
+
+/// Moves the edge of an element facing 'direction' outwards (grow) or inwards (shrink) by 'amount' pixels
+pub fn resize_element(wm_state: &mut WMState, element_ident: LayoutElemID, grow: bool, direction: Direction, amount: u32) -> Result<String, String>{
+    let animation_time = 150;
+
+    let bisect_ident = match find_resizable_bisect(&wm_state.tree, element_ident, direction)? {
+        Some(bisect_ident) => bisect_ident,
+        None => return Err(String::from("There is no split to resize in that direction."))
+    };
+
+    let extent = match wm_state.tree.geometry_of(bisect_ident)? {
+        Some(geometry) => match direction {
+            Direction::LEFT | Direction::RIGHT => geometry.size.w,
+            Direction::UP | Direction::DOWN => geometry.size.h
+        },
+        None => return Err(String::from("The split needs to be placed within the layout."))
+    };
+
+    if extent == 0 {
+        return Err(String::from("The split has no extent to resize."));
+    }
+
+    // Growing towards right/down means that the former child gets a larger share
+    let delta = amount as f32 / extent as f32 * match (grow, direction) {
+        (true, Direction::RIGHT) | (true, Direction::DOWN) | (false, Direction::LEFT) | (false, Direction::UP) => 1f32,
+        _ => -1f32
+    };
+
+    // The child of the container that the resized element is part of
+    let mut child_ident = element_ident;
+    while let Some(parent_ident) = wm_state.tree.try_lookup_element(child_ident)?.and_then(|element| element.parent_id) {
+        if parent_ident == bisect_ident { break; }
+        child_ident = parent_ident;
+    }
+
+    // Splits are resized right away, bisects get their ratio animated
+    let ratio = match wm_state.tree.try_lookup_element(bisect_ident)? {
+        Some(mut element) => match element.profile {
+            LayoutElementProfile::Bisect(ref bisect) => Some(bisect.ratio),
+            LayoutElementProfile::Split(ref mut split) => {
+                // The edge lies between the child and its neighbour on the side of the direction
+                let index = match split.child_index(child_ident) {
+                    Some(index) => index,
+                    None => return Err(String::from("The element is not part of the split."))
+                };
+                let (former, latter) = match direction {
+                    Direction::LEFT | Direction::UP => (index.saturating_sub(1), index),
+                    Direction::RIGHT | Direction::DOWN => (index, index + 1)
+                };
+
+                if !split.shift_weight(latter, former, delta) {
+                    return Err(String::from("There is no room to resize in that direction."));
+                }
+                None
+            },
+            _ => return Err(String::from("Expected element to be a split."))
+        },
+        None => return Err(String::from("Bisect missing in layout."))
+    };
+
+    let ratio = match ratio {
+        Some(ratio) => ratio,
+        None => {
+            LayoutTree::refresh(wm_state);
+            return Ok(format!("Resized [{}] - the weights of [{}] changed.", element_ident, bisect_ident));
+        }
+    };
+
+    let new_ratio = (ratio + delta).max(MIN_RATIO).min(MAX_RATIO);
+    wm_state.tree.animate_property(bisect_ident, "ratio", new_ratio, false, animation_time)?;
+
+    Ok(format!("Resized [{}] - the ratio of [{}] is now {:.2}.", element_ident, bisect_ident, new_ratio))
+}
+
+/// Splits the preselected window, if any, and returns the new unoccupied place beside it
+pub fn seat_preselected(tree: &mut LayoutTree) -> Result<Option<LayoutElemID>, String> {
+    let preselection = match tree.preselection.take() {
+        Some(preselection) => preselection,
+        None => return Ok(None)
+    };
+
+    let target_ident = preselection.target;
+    match tree.try_lookup_element(target_ident)? {
+        Some(element) => match element.profile {
+            LayoutElementProfile::Window(ref window) if window.floating.is_none() => {},
+            _ => return Ok(None)
+        },
+        None => return Ok(None)
+    }
+
+    // The ratio of a bisect is the share of its former child
+    let ratio = if preselection.goes_first() { preselection.ratio } else { 1f32 - preselection.ratio };
+    let (_, bisect) = Bisect::init(target_ident, tree, preselection.orientation(), ratio)?;
+
+    let (former_ident, latter_ident) = {
+        let mut iter = bisect.children_iter().cloned();
+        (iter.next().unwrap(), iter.next().unwrap())
+    };
+
+    let (preoccupied_ident, unoccupied_ident) = match preselection.goes_first() {
+        true => (latter_ident, former_ident),
+        false => (former_ident, latter_ident)
+    };
+
+    // The window keeps its tags while moving down into the bisect
+    tree.tags.handle_element_swap(target_ident, preoccupied_ident);
+
+    if let Some(window_profile) = tree.swap_element_profile(target_ident, LayoutElementProfile::Bisect(bisect))? {
+        tree.reserve_element_identity(preoccupied_ident, window_profile)?;
+    }
+
+    if tree.focused_id == target_ident {
+        tree.focused_id = preoccupied_ident;
+    }
+
+    Ok(Some(unoccupied_ident))
+}
+
+/// Changes the orientation of the bisect (or split) that an element is directly placed in
+pub fn split_element(wm_state: &mut WMState, element_ident: LayoutElemID, orientation: Option<Orientation>) -> Result<String, String>{
+    let parent_ident = match wm_state.tree.try_lookup_element(element_ident)? {
+        Some(element) => element.parent_id,
+        None => return Err(String::from("That element does not exist in the layout."))
+    };
+
+    let parent_ident = match parent_ident {
+        Some(parent_ident) => parent_ident,
+        None => return Err(String::from("The element is not part of a split."))
+    };
+
+    let new_orientation = match wm_state.tree.try_lookup_element(parent_ident)? {
+        Some(mut parent) => match parent.profile {
             LayoutElementProfile::Bisect(ref mut bisect) => {
-                Err(String::from("Unimplemented!"))         
+                // No explicit orientation means toggling
+                bisect.orientation = orientation.unwrap_or(bisect.orientation.opposite());
+                bisect.orientation
             },
-            LayoutElementProfile::Grid(ref mut wrkspc) => {
-                Err(String::from(""))
+            LayoutElementProfile::Split(ref mut split) => {
+                split.orientation = orientation.unwrap_or(split.orientation.opposite());
+                split.orientation
             },
-            _ => Err(String::from("The destination needs to be either a segmentation or a workspace."))
+            _ => return Err(String::from("The element is not part of a split."))
+        },
+        None => return Err(String::from("The element is not part of a split."))
+    };
+
+    LayoutTree::refresh(wm_state);
+
+    Ok(format!("The split of [{}] is now {}.", element_ident, match new_orientation {
+        Orientation::Horizontal => "horizontal",
+        Orientation::Vertical => "vertical"
+    }))
+}
+
+/// The closest tabbed container that an element is part of
+pub fn find_tabbed(tree: &LayoutTree, element_ident: LayoutElemID) -> Result<Option<LayoutElemID>, String> {
+    let mut current = tree.try_lookup_element(element_ident)?.and_then(|element| element.parent_id);
+
+    while let Some(ident) = current {
+        current = match tree.try_lookup_element(ident)? {
+            Some(element) => match element.profile {
+                LayoutElementProfile::Tabbed(_) => return Ok(Some(ident)),
+                LayoutElementProfile::Grid(_) => return Ok(None),
+                _ => element.parent_id
+            },
+            None => None
+        };
+    }
+
+    Ok(None)
+}
+
+/// Brings the tabs holding an element to the front, returns whether any tab changed
+pub fn reveal_element(tree: &LayoutTree, element_ident: LayoutElemID) -> Result<bool, String> {
+    let mut revealed = false;
+    let mut child_ident = element_ident;
+    let mut current = tree.try_lookup_element(element_ident)?.and_then(|element| element.parent_id);
+
+    while let Some(ident) = current {
+        current = match tree.try_lookup_element(ident)? {
+            Some(mut element) => {
+                if let LayoutElementProfile::Tabbed(ref mut tabbed) = element.profile {
+                    revealed |= tabbed.activate_child(child_ident);
+                }
+
+                element.parent_id
+            },
+            None => None
+        };
+
+        child_ident = ident;
+    }
+
+    Ok(revealed)
+}
+
+/// Switches to another tab of the tabbed container that an element is part of, and focuses it
+pub fn switch_tab(wm_state: &mut WMState, element_ident: LayoutElemID, steps: Option<i32>, index: Option<usize>) -> Result<String, String>{
+    let tabbed_ident = match find_tabbed(&wm_state.tree, element_ident)? {
+        Some(tabbed_ident) => tabbed_ident,
+        None => return Err(String::from("The element is not part of a tabbed container."))
+    };
+
+    let active_ident = match wm_state.tree.try_lookup_element(tabbed_ident)? {
+        Some(mut element) => match element.profile {
+            LayoutElementProfile::Tabbed(ref mut tabbed) => {
+                match (steps, index) {
+                    (Some(steps), _) => tabbed.cycle(steps),
+                    (None, Some(index)) => tabbed.set_active_tab(index),
+                    (None, None) => {}
+                }
+
+                tabbed.get_active_child_id()
+            },
+            _ => None
+        },
+        None => None
+    };
+
+    LayoutTree::refresh(wm_state);
+
+    let mut windows = Vec::new();
+    if let Some(active_ident) = active_ident {
+        find_all_windows(&mut windows, true, &wm_state.tree, active_ident)?;
+    }
+
+    match windows.first() {
+        Some(&window_ident) => focus_window(&wm_state.tree, window_ident),
+        None => Ok(String::from("Switched to an unoccupied tab."))
+    }
+}
+
+/// Turns the split that an element is part of into a tabbed container with a tab per window
+pub fn tab_element(wm_state: &mut WMState, element_ident: LayoutElemID) -> Result<String, String>{
+    let target_ident = {
+        let tree = &wm_state.tree;
+
+        match workspace_of(tree, element_ident)? {
+            Some(workspace_ident) if workspace_ident != element_ident => {},
+            _ => return Err(String::from("Only windows and the containers within workspaces can be tabbed."))
         }
+
+        if is_floating(tree, element_ident)? {
+            return Err(String::from("Floating windows can't be tabbed."));
+        }
+
+        // A window on its own is put in a tabbed container of a single tab
+        let parent_ident = tree.parent_of(element_ident)?;
+        match tree.try_lookup_element(parent_ident)? {
+            Some(parent) => match parent.profile {
+                LayoutElementProfile::Bisect(_) | LayoutElementProfile::Split(_) => parent_ident,
+                LayoutElementProfile::Tabbed(_) => return Err(String::from("The element is already a tab.")),
+                _ => element_ident
+            },
+            None => return Err(String::from("Parent element missing in layout."))
+        }
+    };
+
+    let (tabbed_ident, tab_count) = {
+        let tree = &mut wm_state.tree;
+
+        let mut windows = Vec::new();
+        find_all_windows(&mut windows, false, tree, target_ident)?;
+
+        if windows.is_empty() {
+            return Err(String::from("There are no windows to put in tabs."));
+        }
+
+        let container_parent_ident = tree.parent_of(target_ident)?;
+        discard_containers(tree, target_ident, &windows)?;
+
+        let (tabbed_ident, mut tabbed) = Tabbed::init(tree.spawn_dummy_element(Some(container_parent_ident))?, tree, 0)?;
+        for &window_ident in windows.iter() {
+            tabbed.insert_child(window_ident);
+            tree.set_parent(window_ident, Some(tabbed_ident))?;
+        }
+        tabbed.activate_child(element_ident);
+
+        if let Some(mut container_parent) = tree.try_lookup_element(container_parent_ident)? {
+            container_parent.profile.replace_child(target_ident, tabbed_ident);
+        }
+
+        tree.reserve_element_identity(tabbed_ident, LayoutElementProfile::Tabbed(tabbed))?;
+
+        (tabbed_ident, windows.len())
+    };
+
+    LayoutTree::refresh(wm_state);
+
+    Ok(format!("Put {} window(s) in the tabs of [{}].", tab_count, tabbed_ident))
+}
+
+/// Turns the tabbed container that an element is part of back into an even split of its tabs
+pub fn untab_element(wm_state: &mut WMState, element_ident: LayoutElemID) -> Result<String, String>{
+    {
+        let tree = &mut wm_state.tree;
+
+        let tabbed_ident = match find_tabbed(tree, element_ident)? {
+            Some(tabbed_ident) => tabbed_ident,
+            None => return Err(String::from("The element is not part of a tabbed container."))
+        };
+
+        let tabs = match tree.try_lookup_element(tabbed_ident)? {
+            Some(element) => element.profile.children(),
+            None => Vec::new()
+        };
+
+        let parent_ident = tree.parent_of(tabbed_ident)?;
+        let split_ident = tree.spawn_dummy_element(Some(parent_ident))?;
+
+        let mut slots = Vec::new();
+        split_evenly(tree, split_ident, tabs.len(), Orientation::Horizontal, &mut slots)?;
+
+        if let Some(mut parent) = tree.try_lookup_element(parent_ident)? {
+            parent.profile.replace_child(tabbed_ident, split_ident);
+        }
+
+        for (&tab_ident, &slot_ident) in tabs.iter().zip(slots.iter()) {
+            seat_element_in_slot(tree, tab_ident, slot_ident)?;
+        }
+
+        tree.discard_element(tabbed_ident)?;
     }
-    else{
-        Err(String::from("Destination element missing in layout."))
+
+    LayoutTree::refresh(wm_state);
+
+    Ok(String::from("The tabs are now side by side."))
+}
+
+/// Gives the children of the bisect (or split) that an element is directly placed in equal shares
+pub fn rebalance_split(wm_state: &mut WMState, element_ident: LayoutElemID) -> Result<String, String>{
+    let parent_ident = match wm_state.tree.try_lookup_element(element_ident)?.and_then(|element| element.parent_id) {
+        Some(parent_ident) => parent_ident,
+        None => return Err(String::from("The element is not part of a split."))
+    };
+
+    let is_bisect = match wm_state.tree.try_lookup_element(parent_ident)? {
+        Some(mut parent) => match parent.profile {
+            LayoutElementProfile::Bisect(_) => true,
+            LayoutElementProfile::Split(ref mut split) => {
+                split.rebalance();
+                false
+            },
+            _ => return Err(String::from("The element is not part of a split."))
+        },
+        None => return Err(String::from("Parent element missing in layout."))
+    };
+
+    if is_bisect {
+        wm_state.tree.animate_property(parent_ident, "ratio", 0.5f32, false, 150)?;
+    }
+    else {
+        LayoutTree::refresh(wm_state);
     }
+
+    Ok(format!("Rebalanced [{}].", parent_ident))
+}
+
+// Gathers the children of a container, looking through nested containers of the same orientation
+fn collect_flattenable(tree: &LayoutTree, container_ident: LayoutElemID, orientation: Orientation, children: &mut Vec<LayoutElemID>, nested: &mut Vec<LayoutElemID>) -> Result<(), String> {
+    let child_ids = match tree.try_lookup_element(container_ident)? {
+        Some(element) => element.profile.children(),
+        None => return Ok(())
+    };
+
+    for child_ident in child_ids {
+        let child_orientation = match tree.try_lookup_element(child_ident)? {
+            Some(child) => match child.profile {
+                LayoutElementProfile::Bisect(ref bisect) => Some(bisect.orientation),
+                LayoutElementProfile::Split(ref split) => Some(split.orientation),
+                _ => None
+            },
+            None => None
+        };
+
+        if child_orientation == Some(orientation) {
+            nested.push(child_ident);
+
+            // Recursion
+            collect_flattenable(tree, child_ident, orientation, children, nested)?;
+        }
+        else {
+            children.push(child_ident);
+        }
+    }
+
+    Ok(())
+}
+
+/// Turns the nested bisects (or splits) of one orientation that an element is part of into a single split
+pub fn flatten_split(wm_state: &mut WMState, element_ident: LayoutElemID) -> Result<String, String>{
+    let (container_ident, child_count) = {
+        let tree = &mut wm_state.tree;
+
+        let container_ident = match tree.try_lookup_element(element_ident)?.and_then(|element| element.parent_id) {
+            Some(parent_ident) => parent_ident,
+            None => return Err(String::from("The element is not part of a split."))
+        };
+
+        let orientation = match tree.try_lookup_element(container_ident)? {
+            Some(container) => match container.profile {
+                LayoutElementProfile::Bisect(ref bisect) => bisect.orientation,
+                LayoutElementProfile::Split(ref split) => split.orientation,
+                _ => return Err(String::from("The element is not part of a split."))
+            },
+            None => return Err(String::from("Parent element missing in layout."))
+        };
+
+        let mut children = Vec::new();
+        let mut nested = Vec::new();
+        collect_flattenable(tree, container_ident, orientation, &mut children, &mut nested)?;
+
+        // Every child keeps the share of space it had
+        let mut weights: Vec<f32> = Vec::new();
+        for &child_ident in children.iter() {
+            weights.push(match tree.geometry_of(child_ident)? {
+                Some(geometry) => (match orientation {
+                    Orientation::Horizontal => geometry.size.w,
+                    Orientation::Vertical => geometry.size.h
+                } as f32).max(1f32),
+                None => 1f32
+            });
+        }
+
+        for &child_ident in children.iter() {
+            tree.set_parent(child_ident, Some(container_ident))?;
+        }
+
+        for &nested_ident in nested.iter() {
+            tree.discard_element(nested_ident)?;
+        }
+
+        let child_count = children.len();
+        tree.reserve_element_identity(container_ident, LayoutElementProfile::Split(Split::with_children(orientation, children, weights)))?;
+
+        (container_ident, child_count)
+    };
+
+    LayoutTree::refresh(wm_state);
+
+    Ok(format!("[{}] is now a split of {} elements.", container_ident, child_count))
 }
 
+/// The space within the gaps of a workspace
+pub fn inner_geometry_of_workspace(tree: &LayoutTree, workspace_ident: LayoutElemID) -> Result<Option<Geometry>, String> {
+    // The workspace is no longer borrowed once the geometry is looked up
+    let child_ident = match tree.try_lookup_element(workspace_ident)? {
+        Some(element) => match element.profile {
+            LayoutElementProfile::Padding(ref padding) => Some(padding.child_elem_id),
+            _ => None
+        },
+        None => return Ok(None)
+    };
+
+    tree.geometry_of(child_ident.unwrap_or(workspace_ident))
+}
+
+/// A second pass of the arrangement for the windows that are (partly) maximized or fullscreen
+pub fn arrange_expanded_windows(tree: &LayoutTree) -> Result<(), String> {
+    let mut window_ids = Vec::new();
+    find_all_windows(&mut window_ids, false, tree, PARENT_ELEMENT)?;
+
+    for window_ident in window_ids {
+        let (mode, expanded_mode, expansion) = match tree.try_lookup_element(window_ident)? {
+            Some(element) => match element.profile {
+                LayoutElementProfile::Window(ref window) => (window.mode, window.expanded_mode(), window.expansion),
+                _ => continue
+            },
+            None => continue
+        };
+
+        if expansion <= 0f32 || expanded_mode == WindowMode::Tiled {
+            continue;
+        }
+
+        let (workspace_ident, tiled_geometry) = match (workspace_of(tree, window_ident)?, tree.geometry_of(window_ident)?) {
+            (Some(workspace_ident), Some(tiled_geometry)) => (workspace_ident, tiled_geometry),
+            _ => continue
+        };
+
+        // Only the windows of visible workspaces
+        if !tree.get_outer_geometry().overlaps_geometry(tiled_geometry) {
+            continue;
+        }
+
+        let expanded_geometry = match expanded_mode {
+            WindowMode::Fullscreen => tree.get_outer_geometry(),
+            _ => match inner_geometry_of_workspace(tree, workspace_ident)? {
+                Some(geometry) => geometry,
+                None => continue
+            }
+        };
+
+        if mode == WindowMode::Fullscreen {
+            let mut neighbour_ids = Vec::new();
+            find_all_windows(&mut neighbour_ids, false, tree, workspace_ident)?;
+
+            for neighbour_ident in neighbour_ids.into_iter().filter(|&ident| ident != window_ident) {
+                if let Some(neighbour) = tree.try_lookup_element(neighbour_ident)? {
+                    if let LayoutElementProfile::Window(ref window) = neighbour.profile {
+                        if let Some(view) = window.get_view() {
+                            view.set_visibility(Visibility::Null);
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(mut element) = tree.try_lookup_element(window_ident)? {
+            if let LayoutElementProfile::Window(ref mut window) = element.profile {
+                window.set_desired_geometry(tiled_geometry.interpolated(expanded_geometry, expansion));
+
+                if let Some(view) = window.get_view() {
+                    view.bring_to_front();
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Puts a window in a mode, or back to tiled if it is already in it and 'toggle' is set
+pub fn set_window_mode(wm_state: &mut WMState, element_ident: LayoutElemID, mode: WindowMode, toggle: bool) -> Result<String, String>{
+    let animation_time = 200;
+
+    let new_mode = match wm_state.tree.try_lookup_element(element_ident)? {
+        Some(mut element) => match element.profile {
+            LayoutElementProfile::Window(ref mut window) => {
+                let new_mode = if toggle && window.mode == mode { WindowMode::Tiled } else { mode };
+
+                // Going straight from one expanded mode to another starts over from the tiled geometry
+                if new_mode != WindowMode::Tiled && window.mode != WindowMode::Tiled && window.mode != new_mode {
+                    window.expansion = 0f32;
+                }
+
+                window.set_mode(new_mode);
+                new_mode
+            },
+            _ => return Err(String::from("Only windows can be maximized or made fullscreen."))
+        },
+        None => return Err(String::from("That element does not exist in the layout."))
+    };
+
+    let expansion = if new_mode == WindowMode::Tiled { 0f32 } else { 1f32 };
+    wm_state.tree.animate_property(element_ident, "expansion", expansion, false, animation_time)?;
+
+    LayoutTree::refresh(wm_state);
+
+    Ok(format!("[{}] is now {:?}.", element_ident, new_mode))
+}
+
+/// Splits a place into 'count' evenly sized places in a row (or column), adding them to 'slots' in order
+pub fn split_evenly(tree: &mut LayoutTree, element_ident: LayoutElemID, count: usize, orientation: Orientation, slots: &mut Vec<LayoutElemID>) -> Result<(), String> {
+    if count <= 1 {
+        slots.push(element_ident);
+        return Ok(());
+    }
+
+    let (_, split) = Split::init(element_ident, tree, orientation, count)?;
+    slots.extend(split.children_iter().cloned());
+
+    tree.reserve_element_identity(element_ident, LayoutElementProfile::Split(split))
+}
+
+/// Discards everything beneath an element (and the element itself) except for the elements to keep
+pub fn discard_containers(tree: &mut LayoutTree, element_ident: LayoutElemID, elements_to_keep: &[LayoutElemID]) -> Result<(), String> {
+    if elements_to_keep.contains(&element_ident) {
+        return Ok(());
+    }
+
+    let child_ids = match tree.try_lookup_element(element_ident)? {
+        Some(element) => element.profile.children(),
+        None => return Ok(())
+    };
+
+    for child_ident in child_ids {
+        // Recursion
+        discard_containers(tree, child_ident, elements_to_keep)?;
+    }
+
+    tree.discard_element(element_ident)
+}
+
+/// Discards an element along with everything beneath it
+pub fn discard_subtree(tree: &mut LayoutTree, element_ident: LayoutElemID) -> Result<(), String> {
+    let child_ids = match tree.try_lookup_element(element_ident)? {
+        Some(element) => element.profile.children(),
+        None => return Ok(())
+    };
+
+    for child_ident in child_ids {
+        // Recursion
+        discard_subtree(tree, child_ident)?;
+    }
+
+    tree.discard_element(element_ident)
+}
+
+/// Arranges a workspace by another policy - its tiled windows are seated anew, in the order they had
+pub fn set_workspace_policy(wm_state: &mut WMState, workspace_ident: LayoutElemID, mut policy: Box<LayoutPolicy>) -> Result<String, String> {
+    {
+        let tree = &mut wm_state.tree;
+
+        if workspace_of(tree, workspace_ident)? != Some(workspace_ident) {
+            return Err(String::from("Only workspaces can be given a layout policy."));
+        }
+
+        let former_root_ident = match tree.try_lookup_element(workspace_ident)? {
+            Some(workspace) => match workspace.profile {
+                LayoutElementProfile::Padding(ref padding) => padding.child_elem_id,
+                _ => return Err(String::from("Expected the workspace to be a padding."))
+            },
+            None => return Err(String::from("That workspace does not exist in the layout."))
+        };
+
+        let mut windows = Vec::new();
+        find_all_windows(&mut windows, false, tree, workspace_ident)?;
+        let mut tiled_windows = Vec::new();
+        for window_ident in windows {
+            if !is_floating(tree, window_ident)? {
+                tiled_windows.push(window_ident);
+            }
+        }
+        let windows = tiled_windows;
+
+        // A preselected place would not survive the new arrangement
+        let preselection_target = tree.preselection.as_ref().map(|preselection| preselection.target);
+        let preselected_here = match preselection_target {
+            Some(target) => tree.is_descendant_of(target, workspace_ident)?,
+            None => false
+        };
+        if preselected_here {
+            tree.preselection = None;
+        }
+
+        discard_containers(tree, former_root_ident, &windows)?;
+
+        let root_ident = tree.spawn_dummy_element(Some(workspace_ident))?;
+        if let Some(mut workspace) = tree.try_lookup_element(workspace_ident)? {
+            workspace.profile.replace_child(former_root_ident, root_ident);
+        }
+
+        for &window_ident in windows.iter() {
+            tree.set_parent(window_ident, None)?;
+        }
+
+        for &window_ident in windows.iter() {
+            let slot_ident = policy.seat_window(tree, workspace_ident)?;
+            seat_element_in_slot(tree, window_ident, slot_ident)?;
+        }
+
+        tree.set_policy_of(workspace_ident, policy);
+    }
+
+    LayoutTree::refresh(wm_state);
+
+    Ok(format!("Workspace [{}] is arranged by '{}' now.", workspace_ident, wm_state.tree.policy_of(workspace_ident).name()))
+}
+
+/// Adds a row or column of workspaces next to the active workspace
+pub fn insert_workspaces(wm_state: &mut WMState, axis: GridAxis) -> Result<String, String> {
+    let grid_ident = match find_grid(&wm_state.tree, PARENT_ELEMENT)? {
+        Some(grid_ident) => grid_ident,
+        None => return Err(String::from("There is no grid of workspaces in the layout."))
+    };
+
+    let count = match wm_state.tree.try_lookup_element(grid_ident)? {
+        Some(element) => match element.profile {
+            LayoutElementProfile::Grid(ref grid) => match axis { GridAxis::Row => grid.columns(), GridAxis::Column => grid.rows() },
+            _ => return Err(String::from("Expected element to be a grid."))
+        },
+        None => return Err(String::from("Grid missing in layout."))
+    };
+
+    let mut new_children = Vec::new();
+    for _ in 0..count {
+        let child_ident = Grid::spawn_subspace(grid_ident, &mut wm_state.tree)?;
+
+        wm_state.tree.tags.tag_element("sub", child_ident);
+        new_children.push(child_ident);
+    }
+
+    let (new_indices, dimensions) = match wm_state.tree.try_lookup_element(grid_ident)? {
+        Some(mut element) => match element.profile {
+            LayoutElementProfile::Grid(ref mut grid) => {
+                let active_subspace = grid.active_subspace();
+                let new_indices = match axis {
+                    GridAxis::Row => { let row = grid.row_of(active_subspace); grid.insert_row(row, new_children) },
+                    GridAxis::Column => { let column = grid.column_of(active_subspace); grid.insert_column(column, new_children) }
+                };
+
+                (new_indices, (grid.columns(), grid.rows()))
+            },
+            _ => return Err(String::from("Expected element to be a grid."))
+        },
+        None => return Err(String::from("Grid missing in layout."))
+    };
+
+    retag_workspaces(wm_state, grid_ident)?;
+
+    wm_state.tree.layout_policy.workspaces_inserted(&new_indices);
+    for policy in wm_state.tree.workspace_policies.values_mut() {
+        policy.workspaces_inserted(&new_indices);
+    }
+    broadcast(Event::Workspaces { columns: dimensions.0, rows: dimensions.1 });
+
+    wm_state.jump_to_active_workspace(300);
+    LayoutTree::refresh(wm_state);
+
+    Ok(format!("Inserted {} workspace(s), the grid is now {}x{}.", count, dimensions.0, dimensions.1))
+}
+
+/// Removes the row or column of workspaces that 'workspace_ident' is part of, given that all of them are empty
+pub fn remove_workspaces(wm_state: &mut WMState, workspace_ident: LayoutElemID, axis: GridAxis) -> Result<String, String> {
+    let grid_ident = match find_grid(&wm_state.tree, PARENT_ELEMENT)? {
+        Some(grid_ident) => grid_ident,
+        None => return Err(String::from("There is no grid of workspaces in the layout."))
+    };
+
+    let index = match wm_state.tree.workspace_index(workspace_ident)? {
+        Some(index) => index,
+        None => return Err(String::from("That element is not a workspace."))
+    };
+
+    let removed_children = match wm_state.tree.try_lookup_element(grid_ident)? {
+        Some(element) => match element.profile {
+            LayoutElementProfile::Grid(ref grid) => match axis {
+                GridAxis::Row if grid.rows() > 1 => grid.row_children(grid.row_of(index)),
+                GridAxis::Column if grid.columns() > 1 => grid.column_children(grid.column_of(index)),
+                _ => return Err(String::from("The last row or column of workspaces can't be removed."))
+            },
+            _ => return Err(String::from("Expected element to be a grid."))
+        },
+        None => return Err(String::from("Grid missing in layout."))
+    };
+
+    for child_ident in removed_children.iter() {
+        let mut windows = Vec::new();
+        find_all_windows(&mut windows, false, &wm_state.tree, *child_ident)?;
+
+        if !windows.is_empty() {
+            return Err(format!("Workspace [{}] still has windows in it.", child_ident));
+        }
+    }
+
+    let (removed_indices, dimensions) = match wm_state.tree.try_lookup_element(grid_ident)? {
+        Some(mut element) => match element.profile {
+            LayoutElementProfile::Grid(ref mut grid) => {
+                let removed_indices = match axis {
+                    GridAxis::Row => { let row = grid.row_of(index); grid.remove_row(row) },
+                    GridAxis::Column => { let column = grid.column_of(index); grid.remove_column(column) }
+                };
+
+                (removed_indices, (grid.columns(), grid.rows()))
+            },
+            _ => return Err(String::from("Expected element to be a grid."))
+        },
+        None => return Err(String::from("Grid missing in layout."))
+    };
+
+    for child_ident in removed_children.iter() {
+        discard_subtree(&mut wm_state.tree, *child_ident)?;
+        wm_state.tree.workspace_policies.remove(child_ident);
+    }
+
+    retag_workspaces(wm_state, grid_ident)?;
+
+    wm_state.tree.layout_policy.workspaces_removed(&removed_indices);
+    for policy in wm_state.tree.workspace_policies.values_mut() {
+        policy.workspaces_removed(&removed_indices);
+    }
+    broadcast(Event::Workspaces { columns: dimensions.0, rows: dimensions.1 });
+
+    wm_state.jump_to_active_workspace(300);
+    LayoutTree::refresh(wm_state);
+
+    Ok(format!("Removed {} workspace(s), the grid is now {}x{}.", removed_children.len(), dimensions.0, dimensions.1))
+}
+
+/// Gives every workspace the tag of the position it holds in the grid now, as rows and columns shift
+fn retag_workspaces(wm_state: &mut WMState, grid_ident: LayoutElemID) -> Result<(), String> {
+    let positions: Vec<(usize, usize, LayoutElemID)> = match wm_state.tree.try_lookup_element(grid_ident)? {
+        Some(element) => match element.profile {
+            LayoutElementProfile::Grid(ref grid) => grid.children_iter().enumerate()
+                .map(|(index, child_ident)| (grid.row_of(index), grid.column_of(index), *child_ident))
+                .collect(),
+            _ => return Ok(())
+        },
+        None => return Ok(())
+    };
+
+    // All of the former ones go first, so that no position tag is handed on while still held by another workspace
+    for &(_, _, child_ident) in positions.iter() {
+        for tag in wm_state.tree.tags.address_tags_by_element(child_ident) {
+            if wm_state.config.layout.is_workspace_tag(&tag) {
+                wm_state.tree.tags.remove_tag(&tag, false);
+            }
+        }
+    }
+
+    for &(row, column, child_ident) in positions.iter() {
+        let tag = wm_state.config.layout.workspace_tag(row, column);
+        wm_state.tree.tags.bind_tag(&tag, child_ident);
+    }
+
+    Ok(())
+}
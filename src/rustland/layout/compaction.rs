@@ -0,0 +1,134 @@
+use std::collections::HashSet;
+
+use common::definitions::LayoutElemID;
+use layout::{LayoutTree, PARENT_ELEMENT};
+use layout::element::LayoutElementProfile;
+use layout::element::bisect::{MIN_RATIO, MAX_RATIO};
+use layout::arrangement::detach_element;
+use wmstate::*;
+
+/*
+    Tidying up after closed windows - collapsing the places they left behind and recycling element identities
+*/
+
+/// Whether an element is an unoccupied slot that nothing refers to - by name or otherwise
+fn is_disposable(tree: &LayoutTree, element_ident: LayoutElemID, referred: &[LayoutElemID]) -> Result<bool, String> {
+    if referred.contains(&element_ident) || !tree.tags.persistent_tags(element_ident).is_empty() {
+        return Ok(false);
+    }
+
+    let unoccupied = match tree.try_lookup_element(element_ident)? {
+        Some(element) => element.profile.is_none(),
+        None => false
+    };
+
+    Ok(unoccupied)
+}
+
+/// Collapses the bisects beneath an element that give (next to) no space to an unoccupied slot
+fn collapse_slivers(tree: &mut LayoutTree, element_ident: LayoutElemID, referred: &[LayoutElemID], collapsed: &mut usize) -> Result<(), String> {
+    let child_ids = match tree.try_lookup_element(element_ident)? {
+        Some(element) => element.profile.children(),
+        None => return Ok(())
+    };
+
+    for child_ident in child_ids {
+        // Recursion
+        collapse_slivers(tree, child_ident, referred, collapsed)?;
+    }
+
+    // A ratio still in transition is left for a later pass
+    let squeezed_ids: Vec<LayoutElemID> = match tree.try_lookup_element(element_ident)? {
+        Some(element) => match element.profile {
+            LayoutElementProfile::Bisect(ref bisect) => bisect.children_iter().cloned().enumerate()
+                .filter(|&(index, _)| match index { 0 => bisect.ratio < MIN_RATIO, _ => bisect.ratio > MAX_RATIO })
+                .map(|(_, child_ident)| child_ident)
+                .collect(),
+            _ => Vec::new()
+        },
+        None => Vec::new()
+    };
+
+    let mut sliver_ident = None;
+    for child_ident in squeezed_ids {
+        if is_disposable(tree, child_ident, referred)? {
+            sliver_ident = Some(child_ident);
+            break;
+        }
+    }
+
+    if let Some(sliver_ident) = sliver_ident {
+        // The remaining child takes over the place of the bisect
+        if detach_element(tree, sliver_ident).is_ok() {
+            tree.discard_element(sliver_ident)?;
+            *collapsed += 1;
+        }
+    }
+
+    Ok(())
+}
+
+/// Collapses slivers of unoccupied slots left by closed windows, and removes the elements that are no longer
+/// part of the layout so that their identities can be handed out again
+pub fn compact(wm_state: &mut WMState) -> Result<String, String> {
+    let (collapsed, removed) = {
+        let &mut WMState{ ref mut tree, ref input_dev, .. } = wm_state;
+
+        // Elements that are referred to from outside of the layout are left alone
+        let mut referred: Vec<LayoutElemID> = tree.session_seats.iter().map(|seat| seat.slot_ident).collect();
+        if let Some(ref input_dev) = *input_dev {
+            referred.extend(input_dev.drag);
+            if let Some((resize_ident, _)) = input_dev.resize {
+                referred.push(resize_ident);
+            }
+        }
+        referred.push(tree.focused_id);
+        if let Some(ref preselection) = tree.preselection {
+            referred.push(preselection.target);
+        }
+        match ACTIVE_TRANSITIONS.lock() {
+            Ok(active_transitions) => referred.extend(active_transitions.iter().map(|transition| transition.element_id)),
+            Err(_) => return Err(String::from("Couldn't get a lock on the transitions!"))
+        }
+
+        let mut collapsed = 0;
+        collapse_slivers(tree, PARENT_ELEMENT, &referred, &mut collapsed)?;
+
+        // Everything beneath the root, the scratchpad (or any other element standing on its own) is still in use
+        let mut pending: Vec<LayoutElemID> = Vec::new();
+        for element_ident in tree.get_all_element_ids() {
+            let standalone = match tree.try_lookup_element(element_ident)? {
+                Some(element) => element.parent_id.is_none() && !element.profile.is_none(),
+                None => false
+            };
+
+            if element_ident == PARENT_ELEMENT || referred.contains(&element_ident) || standalone {
+                pending.push(element_ident);
+            }
+        }
+        let mut in_use = HashSet::new();
+
+        while let Some(element_ident) = pending.pop() {
+            if in_use.insert(element_ident) {
+                if let Some(element) = tree.try_lookup_element(element_ident)? {
+                    pending.extend(element.profile.children());
+                }
+            }
+        }
+
+        let unused: Vec<LayoutElemID> = tree.get_all_element_ids().into_iter().filter(|element_ident| !in_use.contains(element_ident)).collect();
+        for &element_ident in unused.iter() {
+            tree.remove_element(element_ident);
+        }
+
+        tree.tags.prune_unused();
+
+        (collapsed, unused.len())
+    };
+
+    if collapsed > 0 {
+        LayoutTree::refresh(wm_state);
+    }
+
+    Ok(format!("Collapsed {} container(s) and removed {} element(s).", collapsed, removed))
+}
@@ -11,7 +11,11 @@ pub use wlc::{Geometry, Point, Size};
 
 pub const LOWER_SEGM_BOUND: i32 = 0;
 
-#[derive(Serialize, Deserialize, Clone)]
+// the limits of the ratio when resizing - neither side should be squeezed away completely
+pub const MIN_RATIO: f32 = 0.1;
+pub const MAX_RATIO: f32 = 0.9;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub enum Orientation{
     Horizontal,
     Vertical,
@@ -56,6 +60,9 @@ impl PartialEq for Side {
 }
 impl Eq for Side {}
 
+// The order that the children of a bisect are arranged in
+static SIDES: [Side; 2] = [Side::Left, Side::Right];
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Bisect{
     children: HashMap<Side, LayoutElemID>,
@@ -64,10 +71,10 @@ pub struct Bisect{
 }
 
 impl Bisect{
-    pub fn init(ident: LayoutElemID, tree: &mut LayoutTree, orientation: Orientation, ratio: f32) -> (LayoutElemID, Bisect) {
+    pub fn init(ident: LayoutElemID, tree: &mut LayoutTree, orientation: Orientation, ratio: f32) -> Result<(LayoutElemID, Bisect), String> {
         let mut children = HashMap::new();
-        children.insert(Side::Left, tree.spawn_dummy_element(Some(ident)));
-        children.insert(Side::Right, tree.spawn_dummy_element(Some(ident)));
+        children.insert(Side::Left, tree.spawn_dummy_element(Some(ident))?);
+        children.insert(Side::Right, tree.spawn_dummy_element(Some(ident))?);
 
         let profile = Bisect{
             children: children,
@@ -75,7 +82,7 @@ impl Bisect{
             ratio: ratio
         };
 
-        (ident, profile)
+        Ok((ident, profile))
     }
 
     pub fn seat_child_on_side(&mut self, side: Side, child_ident: LayoutElemID) -> (Side, Option<LayoutElemID>){
@@ -93,34 +100,58 @@ impl Bisect{
         self.child_side(child_ident)
     }
 
+    pub fn replace_child(&mut self, old_ident: LayoutElemID, new_ident: LayoutElemID) -> bool{
+        for child_ident in self.children.values_mut() {
+            if *child_ident == old_ident {
+                *child_ident = new_ident;
+                return true;
+            }
+        }
+
+        false
+    }
+
+    pub fn sibling_of(&self, child_ident: LayoutElemID) -> Option<LayoutElemID>{
+        if !self.children.values().any(|&e| e == child_ident) {
+            return None;
+        }
+
+        self.children.values().find(|&&e| e != child_ident).cloned()
+    }
+
     pub fn disown_child(&mut self, child_ident: LayoutElemID){
         self.children.retain(|_, &mut v| v != child_ident);
     }
 
 
-    pub fn count_active_children(&self, tree: &LayoutTree) -> i32 {
+    pub fn count_active_children(&self, tree: &LayoutTree) -> Result<i32, String> {
         let mut iter = self.children_iter();
         let mut output = 0;
 
         while let Some(child_ident) = iter.next() {
-            if tree.lookup_element(*child_ident).map_or(false, |child| !child.profile.is_none()) {
+            if tree.try_lookup_element(*child_ident)?.map_or(false, |child| !child.profile.is_none()) {
                 output += 1;
             }
         }
 
-        return output;
+        return Ok(output);
+    }
+
+    /// The position of a child in the order that the bisect is arranged in 
+    pub fn child_index(&self, element_ident: LayoutElemID) -> Option<usize> {
+        self.children_iter().position(|&e| e == element_ident)
     }
 
     pub fn child_side(&self, element_ident: LayoutElemID) -> Side {
-        match self.children.values().position(|&e| e == element_ident){
+        match self.children_iter().position(|&e| e == element_ident){
             Some(p) if p == 0 => Side::Left,
             Some(p) if p == 1 => Side::Right,
             _ => Side::Neither
         }
     }
 
-    pub fn children_iter(&self) -> impl Iterator<Item = &LayoutElemID> {
-        self.children.values()
+    pub fn children_iter<'a>(&'a self) -> impl Iterator<Item = &'a LayoutElemID> {
+        SIDES.iter().filter_map(move |side| self.children.get(side))
     }
 
     pub fn get_orientation(self) -> Orientation{
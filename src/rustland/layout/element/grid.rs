@@ -8,8 +8,32 @@ use layout::element::LayoutElementProfile;
 
 use wlc::*;
 
+#[derive(Clone, Copy, PartialEq)]
 pub enum Direction { LEFT, RIGHT, UP, DOWN }
 
+impl Direction{
+    pub fn parse(repr: &str) -> Option<Direction>{
+        match repr {
+            "left" => Some(Direction::LEFT),
+            "right" => Some(Direction::RIGHT),
+            "up" => Some(Direction::UP),
+            "down" => Some(Direction::DOWN),
+            _ => None
+        }
+    }
+
+    pub fn opposite(&self) -> Direction{
+        match *self {
+            Direction::LEFT => Direction::RIGHT,
+            Direction::RIGHT => Direction::LEFT,
+            Direction::UP => Direction::DOWN,
+            Direction::DOWN => Direction::UP
+        }
+    }
+}
+
+pub enum GridAxis { Row, Column }
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Grid{
     active_subspace: usize,
@@ -19,14 +43,12 @@ pub struct Grid{
 }
 
 impl Grid{
-    pub fn init(ident: LayoutElemID, tree: &mut LayoutTree, columns: usize, rows: usize) -> (LayoutElemID, Grid) {
+    pub fn init(ident: LayoutElemID, tree: &mut LayoutTree, columns: usize, rows: usize) -> Result<(LayoutElemID, Grid), String> {
         assert!(columns * rows > 0, "At least one Grid is required.");
         
         let mut children: Vec<LayoutElemID> = Vec::new();
         for _ in 0..(columns * rows){
-            let (child_ident, child) = Padding::init(tree.spawn_dummy_element(Some(ident)), tree, 200, None);
-            tree.reserve_element_identity(child_ident, LayoutElementProfile::Padding(child));
-            children.push(child_ident);
+            children.push(Grid::spawn_subspace(ident, tree)?);
         }
         
         let profile = Grid{
@@ -36,7 +58,124 @@ impl Grid{
             urgent_subspace_updates: Vec::with_capacity(2)
         };
 
-        (ident, profile)
+        Ok((ident, profile))
+    }
+
+    /// Creates the (empty) element of a new workspace - it is up to the caller to insert it
+    pub fn spawn_subspace(ident: LayoutElemID, tree: &mut LayoutTree) -> Result<LayoutElemID, String> {
+        let (child_ident, child) = Padding::init(tree.spawn_dummy_element(Some(ident))?, tree, 200, None)?;
+        tree.reserve_element_identity(child_ident, LayoutElementProfile::Padding(child))?;
+
+        Ok(child_ident)
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn rows(&self) -> usize {
+        self.subspace_element_ids.len() / self.columns
+    }
+
+    pub fn row_of(&self, subspace: usize) -> usize {
+        subspace / self.columns
+    }
+
+    pub fn column_of(&self, subspace: usize) -> usize {
+        subspace % self.columns
+    }
+
+    /// Inserts a row of workspaces after 'after_row', returning the indices the new workspaces got
+    pub fn insert_row(&mut self, after_row: usize, new_children: Vec<LayoutElemID>) -> Vec<usize> {
+        assert!(new_children.len() == self.columns, "A row needs exactly one workspace per column.");
+
+        let insertion_index = clamp(after_row + 1, 0, self.rows()) * self.columns;
+        let active_child = self.get_active_child_id();
+
+        for (offset, child_ident) in new_children.into_iter().enumerate() {
+            self.subspace_element_ids.insert(insertion_index + offset, child_ident);
+        }
+
+        self.follow_active_child(active_child);
+        (insertion_index..insertion_index + self.columns).collect()
+    }
+
+    /// Inserts a column of workspaces after 'after_column', returning the indices the new workspaces got
+    pub fn insert_column(&mut self, after_column: usize, new_children: Vec<LayoutElemID>) -> Vec<usize> {
+        assert!(new_children.len() == self.rows(), "A column needs exactly one workspace per row.");
+
+        let insertion_column = clamp(after_column + 1, 0, self.columns);
+        let new_columns = self.columns + 1;
+        let active_child = self.get_active_child_id();
+
+        // Rows are stored one after another, so every row has to make room for its new workspace
+        let mut new_indices = Vec::new();
+        for (row, child_ident) in new_children.into_iter().enumerate() {
+            let index = row * new_columns + insertion_column;
+
+            self.subspace_element_ids.insert(index, child_ident);
+            new_indices.push(index);
+        }
+
+        self.columns = new_columns;
+        self.follow_active_child(active_child);
+        new_indices
+    }
+
+    pub fn row_children(&self, row: usize) -> Vec<LayoutElemID> {
+        self.subspace_element_ids.iter().enumerate()
+            .filter(|&(index, _)| self.row_of(index) == row)
+            .map(|(_, ident)| *ident)
+            .collect()
+    }
+
+    pub fn column_children(&self, column: usize) -> Vec<LayoutElemID> {
+        self.subspace_element_ids.iter().enumerate()
+            .filter(|&(index, _)| self.column_of(index) == column)
+            .map(|(_, ident)| *ident)
+            .collect()
+    }
+
+    /// Removes a row of workspaces, returning the indices they had
+    pub fn remove_row(&mut self, row: usize) -> Vec<usize> {
+        assert!(self.rows() > 1, "The last row of workspaces can't be removed.");
+
+        let removed_indices: Vec<usize> = (row * self.columns..(row + 1) * self.columns).collect();
+        self.remove_subspaces(&removed_indices);
+
+        removed_indices
+    }
+
+    /// Removes a column of workspaces, returning the indices they had
+    pub fn remove_column(&mut self, column: usize) -> Vec<usize> {
+        assert!(self.columns > 1, "The last column of workspaces can't be removed.");
+
+        let removed_indices: Vec<usize> = (0..self.subspace_element_ids.len()).filter(|&index| self.column_of(index) == column).collect();
+        self.remove_subspaces(&removed_indices);
+        self.columns -= 1;
+
+        removed_indices
+    }
+
+    fn remove_subspaces(&mut self, removed_indices: &[usize]) {
+        let active_child = self.get_active_child_id();
+        let fallback_subspace = self.active_subspace.saturating_sub(removed_indices.iter().filter(|&&index| index < self.active_subspace).count());
+
+        for index in removed_indices.iter().rev() {
+            self.subspace_element_ids.remove(*index);
+        }
+
+        self.follow_active_child(active_child);
+        if !self.subspace_element_ids.contains(&active_child) {
+            self.set_active_subspace(fallback_subspace as i16);
+        }
+    }
+
+    // Keeps the same workspace active after the indices have changed
+    fn follow_active_child(&mut self, active_child: LayoutElemID) {
+        if let Some(index) = self.subspace_element_ids.iter().position(|&ident| ident == active_child) {
+            self.active_subspace = index;
+        }
     }
 
     pub fn get_active_child_id(&self) -> LayoutElemID {
@@ -76,6 +215,16 @@ impl Grid{
         &self.subspace_element_ids
     }
 
+    pub fn replace_child(&mut self, old_ident: LayoutElemID, new_ident: LayoutElemID) -> bool{
+        match self.subspace_element_ids.iter().position(|&e| e == old_ident) {
+            Some(index) => {
+                self.subspace_element_ids[index] = new_ident;
+                true
+            },
+            None => false
+        }
+    }
+
     pub fn children_iter(&self) -> impl Iterator<Item = &LayoutElemID> {
         self.subspace_element_ids.iter()
     }
@@ -92,3 +241,92 @@ impl Grid{
     }
 }
 }
+
+#[cfg(test)]
+mod test{
+    use super::{Grid, Direction};
+    use wlc::{Geometry, Point, Size};
+
+    // A grid of 'columns' by 'rows' workspaces, whose element identities are their initial indices
+    fn grid_of(columns: usize, rows: usize) -> Grid {
+        Grid{
+            active_subspace: 0,
+            columns: columns,
+            subspace_element_ids: (0..(columns * rows) as u16).collect(),
+            urgent_subspace_updates: Vec::new()
+        }
+    }
+
+    #[test]
+    fn grid_navigation_test(){
+        let mut grid = grid_of(3, 2);
+        assert_eq!((grid.columns(), grid.rows()), (3, 2));
+        assert_eq!((grid.row_of(4), grid.column_of(4)), (1, 1));
+
+        // Moving past an edge keeps the active workspace
+        grid.switch_to_subspace_in_direction(Direction::LEFT);
+        assert_eq!(grid.active_subspace(), 0);
+        grid.switch_to_subspace_in_direction(Direction::UP);
+        assert_eq!(grid.active_subspace(), 0);
+
+        grid.switch_to_subspace_in_direction(Direction::RIGHT);
+        grid.switch_to_subspace_in_direction(Direction::RIGHT);
+        assert_eq!(grid.active_subspace(), 2);
+        grid.switch_to_subspace_in_direction(Direction::RIGHT);
+        assert_eq!(grid.active_subspace(), 2);
+
+        grid.switch_to_subspace_in_direction(Direction::DOWN);
+        assert_eq!(grid.active_subspace(), 5);
+        grid.switch_to_subspace_in_direction(Direction::DOWN);
+        assert_eq!(grid.active_subspace(), 5);
+
+        grid.set_active_subspace(42);
+        assert_eq!(grid.active_subspace(), 5);
+    }
+
+    #[test]
+    fn grid_rows_and_columns_test(){
+        let mut grid = grid_of(2, 2);
+        grid.set_active_subspace(3);
+
+        // [0 1]    [0 1]
+        // [2 3] -> [4 5]
+        //          [2 3]
+        assert_eq!(grid.insert_row(0, vec![4, 5]), vec![2, 3]);
+        assert_eq!(grid.get_all_children(), &vec![0, 1, 4, 5, 2, 3]);
+        assert_eq!(grid.get_active_child_id(), 3);
+        assert_eq!(grid.row_children(1), vec![4, 5]);
+
+        // [0 6 1]
+        // [4 7 5]
+        // [2 8 3]
+        assert_eq!(grid.insert_column(0, vec![6, 7, 8]), vec![1, 4, 7]);
+        assert_eq!(grid.get_all_children(), &vec![0, 6, 1, 4, 7, 5, 2, 8, 3]);
+        assert_eq!(grid.column_children(1), vec![6, 7, 8]);
+        assert_eq!(grid.get_active_child_id(), 3);
+
+        assert_eq!(grid.remove_column(0), vec![0, 3, 6]);
+        assert_eq!(grid.get_all_children(), &vec![6, 1, 7, 5, 8, 3]);
+        assert_eq!(grid.columns(), 2);
+        assert_eq!(grid.get_active_child_id(), 3);
+
+        // Removing the row of the active workspace activates the nearest one left
+        assert_eq!(grid.remove_row(2), vec![4, 5]);
+        assert_eq!(grid.get_all_children(), &vec![6, 1, 7, 5]);
+        assert_eq!(grid.active_subspace(), 3);
+    }
+
+    #[test]
+    fn grid_offset_geometry_test(){
+        let grid = grid_of(3, 2);
+        let display = Geometry{ origin: Point{ x: 0, y: 0 }, size: Size{ w: 1000, h: 500 } };
+        let outer = Geometry{ origin: Point{ x: 10, y: 20 }, size: Size{ w: 1000, h: 500 } };
+
+        let geometry = grid.get_offset_geometry(display, outer, 4, &mut (1f32, 1f32));
+        assert_eq!((geometry.origin.x, geometry.origin.y), (1010, 520));
+        assert_eq!((geometry.size.w, geometry.size.h), (1000, 500));
+
+        let geometry = grid.get_offset_geometry(display, outer, 5, &mut (0.5f32, 0.5f32));
+        assert_eq!((geometry.origin.x, geometry.origin.y), (1010, 270));
+    }
+}
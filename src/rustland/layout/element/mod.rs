@@ -5,9 +5,11 @@ use std::cell::{RefCell, RefMut};
 use std::ops::{Deref, DerefMut};
 
 pub mod bisect;
+pub mod split;
 pub mod padding;
 pub mod window;
 pub mod grid;
+pub mod tabbed;
 
 use common::definitions::LayoutElemID;
 use layout::element::LayoutElementProfile::{Bisect, Padding, Window, Grid};
@@ -41,8 +43,10 @@ impl LayoutElement {
         match new_profile {
             LayoutElementProfile::Bisect(ref bisect) => bisect.register_properties(&mut self.properties),
             LayoutElementProfile::Grid(_) => {},
+            LayoutElementProfile::Tabbed(_) => {},
+            LayoutElementProfile::Split(_) => {},
             LayoutElementProfile::Padding(ref padding) => padding.register_properties(&mut self.properties),
-            LayoutElementProfile::Window(_) => {},
+            LayoutElementProfile::Window(ref window) => window.register_properties(&mut self.properties),
             LayoutElementProfile::None => {}
             _ => { println!("Warning: No properties registered for element {}", self.element_id); }
         }
@@ -71,7 +75,9 @@ impl fmt::Display for LayoutElementProfile {
         write!(f, "{}", 
             match self {
                 &LayoutElementProfile::Bisect(_) => "bisect",
+                &LayoutElementProfile::Split(_) => "split",
                 &LayoutElementProfile::Grid(_) => "grid",
+                &LayoutElementProfile::Tabbed(_) => "tabbed",
                 &LayoutElementProfile::Padding(_) => "padding",
                 &LayoutElementProfile::Window(_) => "window",
                 _ => "n/a"
@@ -88,8 +94,14 @@ pub enum LayoutElementProfile {
     // A container of exactly two child elements
     Bisect(bisect::Bisect),
 
+    // A container of any number of child elements side by side, sized by weight
+    Split(split::Split),
+
     // A container of multiple child elements with only one active in a given moment
     Grid(grid::Grid),
+
+    // A container of multiple child elements within a workspace, showing one of them at a time (as tabs)
+    Tabbed(tabbed::Tabbed),
     
     // A container that can be smaller in relation to the outside geometry 
     Padding(padding::Padding),
@@ -108,6 +120,50 @@ impl LayoutElementProfile {
             _ => false 
         }
     } 
+
+    pub fn is_container(&self) -> bool{
+        match *self {
+            LayoutElementProfile::Bisect(_) | LayoutElementProfile::Split(_) | LayoutElementProfile::Grid(_) | LayoutElementProfile::Tabbed(_) | LayoutElementProfile::Padding(_) => true,
+            _ => false 
+        }
+    }
+
+    pub fn children(&self) -> Vec<LayoutElemID>{
+        match *self {
+            LayoutElementProfile::Bisect(ref bisect) => bisect.children_iter().cloned().collect(),
+            LayoutElementProfile::Split(ref split) => split.get_all_children().clone(),
+            LayoutElementProfile::Grid(ref grid) => grid.get_all_children().clone(),
+            LayoutElementProfile::Tabbed(ref tabbed) => tabbed.get_all_children().clone(),
+            LayoutElementProfile::Padding(ref padding) => {
+                let mut children = vec![padding.child_elem_id];
+                children.extend(padding.floating.iter().cloned());
+                children
+            },
+            _ => Vec::new()
+        }
+    }
+
+    // Puts another element in the place of a child, returns false if there was no such child
+    pub fn replace_child(&mut self, old_ident: LayoutElemID, new_ident: LayoutElemID) -> bool{
+        match *self {
+            LayoutElementProfile::Bisect(ref mut bisect) => bisect.replace_child(old_ident, new_ident),
+            LayoutElementProfile::Split(ref mut split) => split.replace_child(old_ident, new_ident),
+            LayoutElementProfile::Grid(ref mut grid) => grid.replace_child(old_ident, new_ident),
+            LayoutElementProfile::Tabbed(ref mut tabbed) => tabbed.replace_child(old_ident, new_ident),
+            LayoutElementProfile::Padding(ref mut padding) if padding.child_elem_id == old_ident => {
+                padding.child_elem_id = new_ident;
+                true
+            },
+            LayoutElementProfile::Padding(ref mut padding) => match padding.floating.iter().position(|&e| e == old_ident) {
+                Some(position) => {
+                    padding.floating[position] = new_ident;
+                    true
+                },
+                None => false
+            },
+            _ => false
+        }
+    }
 }
 
 trait ElementContainer{
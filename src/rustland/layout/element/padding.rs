@@ -21,21 +21,26 @@ pub struct Padding{
     pub inner_scale_x: f32, 
     pub inner_scale_y: f32,
     pub positioning_offset: Option<Point>,
-    pub frame: Option<Frame>
+    pub frame: Option<Frame>,
+
+    // windows stacked above the child with geometries of their own (the floating layer)
+    #[serde(default)]
+    pub floating: Vec<LayoutElemID>
 }
 
 impl Padding{
-    pub fn init(ident: LayoutElemID, tree: &mut LayoutTree, gap_size: u32, positioning_offset: Option<Point>) -> (LayoutElemID, Padding) {
+    pub fn init(ident: LayoutElemID, tree: &mut LayoutTree, gap_size: u32, positioning_offset: Option<Point>) -> Result<(LayoutElemID, Padding), String> {
         let profile = Padding{
-            child_elem_id: tree.spawn_dummy_element(Some(ident)),
+            child_elem_id: tree.spawn_dummy_element(Some(ident))?,
             gap_size: gap_size,
             inner_scale_x: 1.0f32,
             inner_scale_y: 1.0f32,
             positioning_offset: positioning_offset,
-            frame: None
+            frame: None,
+            floating: Vec::new()
         };
 
-        (ident, profile)
+        Ok((ident, profile))
     }
     
     pub fn apply_frame(&mut self, graphics_program: &GraphicsProgram, initial_opacity: f32){
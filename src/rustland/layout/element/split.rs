@@ -0,0 +1,233 @@
+use std::cmp::max;
+
+use common::definitions::LayoutElemID;
+use layout::LayoutTree;
+use layout::element::bisect::{Orientation, MIN_RATIO, MAX_RATIO};
+
+use wlc::{Geometry, Point, Size};
+
+/*
+    A container of any number of child elements in a row (or column), each given a share according to its weight
+*/
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Split{
+    children: Vec<LayoutElemID>,
+    weights: Vec<f32>,
+    pub orientation: Orientation
+}
+
+impl Split{
+    pub fn init(ident: LayoutElemID, tree: &mut LayoutTree, orientation: Orientation, count: usize) -> Result<(LayoutElemID, Split), String> {
+        let mut children = Vec::new();
+        for _ in 0..count {
+            children.push(tree.spawn_dummy_element(Some(ident))?);
+        }
+
+        let profile = Split{
+            weights: vec![1f32; count],
+            children: children,
+            orientation: orientation
+        };
+
+        Ok((ident, profile))
+    }
+
+    /// A split of existing elements - it is up to the caller to make it their parent
+    pub fn with_children(orientation: Orientation, children: Vec<LayoutElemID>, weights: Vec<f32>) -> Split {
+        assert!(children.len() == weights.len(), "Every child of a split needs a weight.");
+
+        Split{
+            children: children,
+            weights: weights,
+            orientation: orientation
+        }
+    }
+
+    pub fn children_iter(&self) -> impl Iterator<Item = &LayoutElemID> {
+        self.children.iter()
+    }
+
+    pub fn get_all_children(&self) -> &Vec<LayoutElemID> {
+        &self.children
+    }
+
+    pub fn child_count(&self) -> usize {
+        self.children.len()
+    }
+
+    pub fn child_index(&self, element_ident: LayoutElemID) -> Option<usize> {
+        self.children.iter().position(|&e| e == element_ident)
+    }
+
+    pub fn weights(&self) -> &Vec<f32> {
+        &self.weights
+    }
+
+    pub fn set_weight(&mut self, child_index: usize, weight: f32) {
+        if let Some(current) = self.weights.get_mut(child_index) {
+            *current = weight.max(0f32);
+        }
+    }
+
+    /// Inserts a child at a position, with the average weight of the other children
+    pub fn insert_child(&mut self, child_index: usize, child_ident: LayoutElemID) {
+        let index = if child_index > self.children.len() { self.children.len() } else { child_index };
+        let weight = match self.weights.len() {
+            0 => 1f32,
+            n => self.total_weight() / n as f32
+        };
+
+        self.children.insert(index, child_ident);
+        self.weights.insert(index, weight);
+    }
+
+    pub fn disown_child(&mut self, child_ident: LayoutElemID) {
+        if let Some(index) = self.child_index(child_ident) {
+            self.children.remove(index);
+            self.weights.remove(index);
+        }
+    }
+
+    pub fn replace_child(&mut self, old_ident: LayoutElemID, new_ident: LayoutElemID) -> bool{
+        match self.child_index(old_ident) {
+            Some(index) => {
+                self.children[index] = new_ident;
+                true
+            },
+            None => false
+        }
+    }
+
+    /// Gives every child the same share
+    pub fn rebalance(&mut self) {
+        for weight in self.weights.iter_mut() {
+            *weight = 1f32;
+        }
+    }
+
+    /// Moves a fraction of the whole split from one child to another - neither gets squeezed away completely.
+    /// Returns false if nothing could be moved.
+    pub fn shift_weight(&mut self, from_index: usize, to_index: usize, fraction: f32) -> bool {
+        if from_index >= self.weights.len() || to_index >= self.weights.len() || from_index == to_index {
+            return false;
+        }
+
+        let pair = self.weights[from_index] + self.weights[to_index];
+        let to_weight = (self.weights[to_index] + fraction * self.total_weight()).max(MIN_RATIO * pair).min(MAX_RATIO * pair);
+
+        // Already as far as it goes
+        if to_weight == self.weights[to_index] {
+            return false;
+        }
+
+        self.weights[to_index] = to_weight;
+        self.weights[from_index] = pair - to_weight;
+        true
+    }
+
+    fn total_weight(&self) -> f32 {
+        self.weights.iter().fold(0f32, |sum, weight| sum + weight)
+    }
+
+    pub fn get_offset_geometry(&self, outer_geometry: Geometry, stacked_padding: &Option<u32>, child_index: usize) -> Geometry{
+        let padding = (*stacked_padding).unwrap_or(0 as u32) as i32;
+        let total = self.total_weight();
+
+        let extent = match self.orientation {
+            Orientation::Horizontal => outer_geometry.size.w,
+            Orientation::Vertical => outer_geometry.size.h
+        } as f32;
+
+        // The share of the children before this one, and up to and including it
+        let (preceding, up_to) = match total {
+            total if total > 0f32 => (
+                self.weights.iter().take(child_index).fold(0f32, |sum, weight| sum + weight) / total,
+                self.weights.iter().take(child_index + 1).fold(0f32, |sum, weight| sum + weight) / total
+            ),
+            _ => (child_index as f32 / self.children.len() as f32, (child_index + 1) as f32 / self.children.len() as f32)
+        };
+
+        let start = (preceding * extent) as i32 + if child_index == 0 { 0 } else { padding / 2 };
+        let end = (up_to * extent) as i32 - if child_index + 1 >= self.children.len() { 0 } else { padding / 2 };
+        let length = max(0i32, end - start) as u32;
+
+        match self.orientation {
+            Orientation::Horizontal => Geometry{
+                origin: Point{ x: outer_geometry.origin.x + start, y: outer_geometry.origin.y },
+                size: Size{ w: length, h: outer_geometry.size.h }
+            },
+            Orientation::Vertical => Geometry{
+                origin: Point{ x: outer_geometry.origin.x, y: outer_geometry.origin.y + start },
+                size: Size{ w: outer_geometry.size.w, h: length }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test{
+    use super::Split;
+    use layout::element::bisect::Orientation;
+    use wlc::{Geometry, Point, Size};
+
+    fn close_to(a: f32, b: f32) -> bool {
+        (a - b).abs() < 0.0001
+    }
+
+    #[test]
+    fn split_weights_test(){
+        let mut split = Split::with_children(Orientation::Horizontal, vec![1, 2, 3], vec![1f32, 1f32, 2f32]);
+
+        // A new child gets the average weight
+        split.insert_child(1, 4);
+        assert_eq!(split.get_all_children(), &vec![1, 4, 2, 3]);
+        assert!(close_to(split.weights()[1], 4f32 / 3f32));
+
+        split.disown_child(4);
+        assert_eq!(split.get_all_children(), &vec![1, 2, 3]);
+        assert_eq!(split.weights(), &vec![1f32, 1f32, 2f32]);
+
+        // A quarter of the whole split moves over
+        assert!(split.shift_weight(2, 0, 0.25));
+        assert_eq!(split.weights(), &vec![2f32, 1f32, 1f32]);
+
+        // Neither child of the pair gets squeezed below its minimum share
+        assert!(split.shift_weight(1, 0, 1f32));
+        assert!(close_to(split.weights()[0], 2.7));
+        assert!(close_to(split.weights()[1], 0.3));
+        assert!(!split.shift_weight(1, 0, 1f32));
+
+        assert!(!split.shift_weight(0, 0, 0.1));
+        assert!(!split.shift_weight(0, 3, 0.1));
+
+        split.set_weight(2, -1f32);
+        assert_eq!(split.weights()[2], 0f32);
+
+        split.rebalance();
+        assert_eq!(split.weights(), &vec![1f32, 1f32, 1f32]);
+    }
+
+    #[test]
+    fn split_offset_geometry_test(){
+        let outer = Geometry{ origin: Point{ x: 0, y: 0 }, size: Size{ w: 400, h: 100 } };
+        let split = Split::with_children(Orientation::Horizontal, vec![1, 2, 3], vec![1f32, 1f32, 2f32]);
+
+        // The padding is only put between the children
+        let lengths: Vec<(i32, u32)> = (0..3).map(|index| {
+            let geometry = split.get_offset_geometry(outer, &Some(10), index);
+            (geometry.origin.x, geometry.size.w)
+        }).collect();
+        assert_eq!(lengths, vec![(0, 95), (105, 90), (205, 195)]);
+
+        // Without any weight the children share evenly
+        let outer = Geometry{ origin: Point{ x: 0, y: 0 }, size: Size{ w: 400, h: 300 } };
+        let mut split = Split::with_children(Orientation::Vertical, vec![1, 2, 3], vec![1f32, 1f32, 1f32]);
+        for index in 0..3 {
+            split.set_weight(index, 0f32);
+        }
+
+        let geometry = split.get_offset_geometry(outer, &None, 1);
+        assert_eq!((geometry.origin.y, geometry.size.h, geometry.size.w), (100, 100, 400));
+    }
+}
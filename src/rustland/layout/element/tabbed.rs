@@ -0,0 +1,166 @@
+use std::cmp::min;
+
+use common::definitions::LayoutElemID;
+use layout::LayoutTree;
+use sugars::program::GraphicsProgram;
+use sugars::Renderable;
+use sugars::frame::Frame;
+
+use wlc::*;
+
+// The height of the strip above the active tab
+pub const TAB_STRIP_HEIGHT: u32 = 16;
+
+const ACTIVE_TAB_OPACITY: f32 = 0.8f32;
+const INACTIVE_TAB_OPACITY: f32 = 0.3f32;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Tabbed{
+    active_tab: usize,
+    tab_element_ids: Vec<LayoutElemID>,
+
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    tab_frames: Vec<Frame>
+}
+
+impl Tabbed{
+    pub fn init(ident: LayoutElemID, tree: &mut LayoutTree, tabs: usize) -> Result<(LayoutElemID, Tabbed), String> {
+        let mut children = Vec::new();
+        for _ in 0..tabs {
+            children.push(tree.spawn_dummy_element(Some(ident))?);
+        }
+
+        let profile = Tabbed{
+            active_tab: 0,
+            tab_element_ids: children,
+            tab_frames: Vec::new()
+        };
+
+        Ok((ident, profile))
+    }
+
+    pub fn get_active_child_id(&self) -> Option<LayoutElemID> {
+        self.tab_element_ids.get(self.active_tab).cloned()
+    }
+
+    pub fn active_tab(&self) -> usize {
+        self.active_tab
+    }
+
+    pub fn set_active_tab(&mut self, new_tab: usize) {
+        self.active_tab = min(new_tab, self.tab_element_ids.len().saturating_sub(1));
+    }
+
+    /// Activates the tab of a child, returns false if it is no child or already active
+    pub fn activate_child(&mut self, child_ident: LayoutElemID) -> bool {
+        match self.tab_element_ids.iter().position(|&e| e == child_ident) {
+            Some(index) if index != self.active_tab => {
+                self.active_tab = index;
+                true
+            },
+            _ => false
+        }
+    }
+
+    /// Steps through the tabs, wrapping around at the ends
+    pub fn cycle(&mut self, steps: i32) {
+        let tabs = self.tab_element_ids.len() as i32;
+
+        if tabs > 0 {
+            self.active_tab = (((self.active_tab as i32 + steps) % tabs + tabs) % tabs) as usize;
+        }
+    }
+
+    /// Adds a tab after the active one and activates it
+    pub fn insert_child(&mut self, child_ident: LayoutElemID) {
+        let index = min(self.active_tab + 1, self.tab_element_ids.len());
+
+        self.tab_element_ids.insert(index, child_ident);
+        self.active_tab = index;
+    }
+
+    pub fn disown_child(&mut self, child_ident: LayoutElemID) {
+        if let Some(index) = self.tab_element_ids.iter().position(|&e| e == child_ident) {
+            self.tab_element_ids.remove(index);
+
+            if index < self.active_tab || self.active_tab >= self.tab_element_ids.len() {
+                self.active_tab = self.active_tab.saturating_sub(1);
+            }
+        }
+    }
+
+    pub fn tab_count(&self) -> usize {
+        self.tab_element_ids.len()
+    }
+
+    pub fn get_all_children(&self) -> &Vec<LayoutElemID> {
+        &self.tab_element_ids
+    }
+
+    pub fn replace_child(&mut self, old_ident: LayoutElemID, new_ident: LayoutElemID) -> bool{
+        match self.tab_element_ids.iter().position(|&e| e == old_ident) {
+            Some(index) => {
+                self.tab_element_ids[index] = new_ident;
+                true
+            },
+            None => false
+        }
+    }
+
+    pub fn children_iter(&self) -> impl Iterator<Item = &LayoutElemID> {
+        self.tab_element_ids.iter()
+    }
+
+    /// The geometry of a child - inactive tabs are put out of sight, like inactive workspaces
+    pub fn get_offset_geometry(&self, display_geometry: Geometry, outer_geometry: Geometry, child_index: usize) -> Geometry{
+        let content_size = Size{
+            w: outer_geometry.size.w,
+            h: outer_geometry.size.h.saturating_sub(TAB_STRIP_HEIGHT)
+        };
+
+        if child_index == self.active_tab {
+            Geometry{
+                origin: Point{ x: outer_geometry.origin.x, y: outer_geometry.origin.y + TAB_STRIP_HEIGHT as i32 },
+                size: content_size
+            }
+        }
+        else {
+            Geometry{
+                origin: Point{
+                    x: display_geometry.origin.x + display_geometry.size.w as i32 + 1,
+                    y: display_geometry.origin.y + display_geometry.size.h as i32 + 1
+                },
+                size: content_size
+            }
+        }
+    }
+
+    /// The part of the tab strip that belongs to a tab
+    pub fn get_tab_geometry(&self, outer_geometry: Geometry, child_index: usize) -> Geometry{
+        let tabs = self.tab_element_ids.len() as u32;
+        let tab_w = if tabs > 0 { outer_geometry.size.w / tabs } else { outer_geometry.size.w };
+
+        Geometry{
+            origin: Point{ x: outer_geometry.origin.x + (child_index as u32 * tab_w) as i32, y: outer_geometry.origin.y },
+            size: Size{ w: tab_w.saturating_sub(1), h: TAB_STRIP_HEIGHT }
+        }
+    }
+}
+
+impl Renderable for Tabbed {
+    fn draw(&mut self, program: &GraphicsProgram, viewport: Geometry){
+        while self.tab_frames.len() < self.tab_element_ids.len() {
+            self.tab_frames.push(Frame::new(program.id, INACTIVE_TAB_OPACITY));
+        }
+        self.tab_frames.truncate(self.tab_element_ids.len());
+
+        for index in 0..self.tab_frames.len() {
+            let tab_geometry = self.get_tab_geometry(viewport, index);
+            let frame = &mut self.tab_frames[index];
+
+            frame.opacity = if index == self.active_tab { ACTIVE_TAB_OPACITY } else { INACTIVE_TAB_OPACITY };
+            frame.draw(program, tab_geometry);
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use std::option::*;
 use std::process::Child;
+use num::clamp;
+use num::traits::cast;
 
 use layout::*;
 use layout::element::ElementPropertyProvider;
@@ -8,9 +10,18 @@ use sugars::frame::Frame;
 use utils::geometry::GeometryExt;
 use wmstate::WMState;
 
-use wlc::{View, WeakView, ResizeEdge};
+use wlc::{View, WeakView, ResizeEdge, ViewState};
 use gl::types::GLuint;
 
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum WindowMode {
+    Tiled,
+    // fills the inner space of its workspace
+    Maximized,
+    // covers the whole output and hides the other windows of its workspace
+    Fullscreen
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Window {
     #[serde(skip_serializing)]
@@ -19,21 +30,40 @@ pub struct Window {
 
     desired_geometry: Geometry,
     inner_offset: Option<u32>,
+    pub offset_x: f32,
+    pub offset_y: f32,
+
+    // the geometry of a floating window relative to its workspace - none when tiled
+    pub floating: Option<Geometry>,
+
+    // the mode of the window - and the one it (last) expanded into, for as long as it is shrinking back
+    pub mode: WindowMode,
+    expanded_mode: WindowMode,
+
+    // how far (0 to 1) the window has gone from its tiled geometry towards the one of its expanded mode 
+    pub expansion: f32,
+
     pub frame: Option<Frame>
 }
 
 impl Window{
-    fn init(tree: &mut LayoutTree, parent_id: LayoutElemID, weak_view: WeakView, child_process: Child) -> (LayoutElemID, Window) {
-        let window_ident = tree.spawn_dummy_element(Some(parent_id));
+    fn init(tree: &mut LayoutTree, parent_id: LayoutElemID, weak_view: WeakView, child_process: Child) -> Result<(LayoutElemID, Window), String> {
+        let window_ident = tree.spawn_dummy_element(Some(parent_id))?;
         
         let window = Window{
             weak_view: Some(weak_view), 
             desired_geometry: Geometry::zero(),
             inner_offset: None,
+            offset_x: 0f32,
+            offset_y: 0f32,
+            floating: None,
+            mode: WindowMode::Tiled,
+            expanded_mode: WindowMode::Tiled,
+            expansion: 0f32,
             frame: None
         };
 
-        (window_ident, window)
+        Ok((window_ident, window))
     }
 
     pub fn init_dummy() -> Window{
@@ -41,6 +71,12 @@ impl Window{
             weak_view: None,
             desired_geometry: Geometry::zero(),
             inner_offset: None,
+            offset_x: 0f32,
+            offset_y: 0f32,
+            floating: None,
+            mode: WindowMode::Tiled,
+            expanded_mode: WindowMode::Tiled,
+            expansion: 0f32,
             frame: None
         }
     }
@@ -66,10 +102,57 @@ impl Window{
         }
     }
 
+    /// A gap of its own between the window and the edges of its place 
+    pub fn set_inner_offset(&mut self, inner_offset: Option<u32>){
+        self.inner_offset = inner_offset;
+    }
+
     pub fn get_desired_geometry(&self) -> Geometry{
         self.desired_geometry
     }
 
+    /// Switches mode - the caller animates 'expansion' towards 1 (expanded) or 0 (tiled) 
+    pub fn set_mode(&mut self, mode: WindowMode){
+        if mode != WindowMode::Tiled {
+            self.expanded_mode = mode;
+        }
+
+        if let Some(view) = self.get_view() {
+            view.set_state(ViewState::Fullscreen, mode == WindowMode::Fullscreen);
+        }
+
+        self.mode = mode;
+    }
+
+    pub fn expanded_mode(&self) -> WindowMode{
+        self.expanded_mode
+    }
+
+    /// The geometry of a floating window placed in a workspace 
+    pub fn get_floating_geometry(&self, workspace_geometry: Geometry, stacked_scale: &(f32, f32)) -> Option<Geometry>{
+        self.floating.map(|relative| Geometry{
+            origin: Point{
+                x: workspace_geometry.origin.x + (relative.origin.x as f32 * stacked_scale.0) as i32,
+                y: workspace_geometry.origin.y + (relative.origin.y as f32 * stacked_scale.1) as i32
+            },
+            size: Size{
+                w: (relative.size.w as f32 * stacked_scale.0) as u32,
+                h: (relative.size.h as f32 * stacked_scale.1) as u32
+            }
+        })
+    }
+
+    /// The geometry within 'outer_geometry' displaced by the (transient) offset of the window
+    pub fn get_offset_geometry(&self, outer_geometry: Geometry) -> Geometry{
+        Geometry{
+            origin: Point{
+                x: outer_geometry.origin.x + self.offset_x as i32,
+                y: outer_geometry.origin.y + self.offset_y as i32
+            },
+            size: outer_geometry.size
+        }
+    }
+
     pub fn set_desired_geometry(&mut self, geometry: Geometry){
         self.desired_geometry = geometry;
 
@@ -106,6 +189,10 @@ impl Window{
 
 impl ElementPropertyProvider for Window{
     fn register_properties(&self, property_bank: &mut PropertyBank){    
+        property_bank.address_property("offset_x", make_property_handle!(Window, f32, offset_x));
+        property_bank.address_property("offset_y", make_property_handle!(Window, f32, offset_y));
+        property_bank.address_property("expansion", make_property_handle!(Window, f32, expansion, 0f32, 1f32));
+
         property_bank.address_property("frame_opacity", |profile: &mut LayoutElementProfile, new_value: Option<DefaultNumericType>| {
             assist_property_handle!(Window, profile, window, {
                 if let Some(ref mut frame) = window.frame{
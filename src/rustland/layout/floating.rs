@@ -0,0 +1,148 @@
+use common::definitions::LayoutElemID;
+use layout::LayoutTree;
+use layout::element::LayoutElementProfile;
+use layout::arrangement::{workspace_of, detach_element, seat_element_in_slot};
+use wmstate::WMState;
+use utils::geometry::GeometryExt;
+
+use wlc::{Point, Size, Geometry};
+
+/*
+    The floating layer of a workspace - windows placed at a geometry relative to the workspace, above its tiling
+*/
+
+pub fn is_floating(tree: &LayoutTree, element_ident: LayoutElemID) -> Result<bool, String> {
+    let floating = match tree.try_lookup_element(element_ident)? {
+        Some(element) => match element.profile {
+            LayoutElementProfile::Window(ref window) => window.floating.is_some(),
+            _ => false
+        },
+        None => false
+    };
+
+    Ok(floating)
+}
+
+/// Adds an unoccupied place to the floating layer of a workspace, along with the relative geometry that corresponds to 'geometry'
+pub fn seat_floating(tree: &mut LayoutTree, workspace_ident: LayoutElemID, geometry: Geometry) -> Result<Option<(LayoutElemID, Geometry)>, String> {
+    let workspace_geometry = match tree.geometry_of(workspace_ident)? {
+        Some(workspace_geometry) => workspace_geometry,
+        None => return Ok(None)
+    };
+
+    // Clients that haven't decided on a size get centered in half of the workspace
+    let geometry = match geometry.size.w == 0 || geometry.size.h == 0 {
+        true => Geometry::new(
+            Point{
+                x: workspace_geometry.origin.x + workspace_geometry.size.w as i32 / 4,
+                y: workspace_geometry.origin.y + workspace_geometry.size.h as i32 / 4
+            },
+            Size{ w: workspace_geometry.size.w / 2, h: workspace_geometry.size.h / 2 }
+        ),
+        false => geometry
+    };
+
+    let slot_ident = tree.spawn_dummy_element(Some(workspace_ident))?;
+    let seated = match tree.try_lookup_element(workspace_ident)? {
+        Some(mut workspace) => match workspace.profile {
+            LayoutElementProfile::Padding(ref mut padding) => {
+                padding.floating.push(slot_ident);
+                true
+            },
+            _ => false
+        },
+        None => false
+    };
+
+    if !seated {
+        tree.discard_element(slot_ident)?;
+        return Ok(None);
+    }
+
+    let relative_geometry = Geometry::new(
+        Point{ 
+            x: geometry.origin.x - workspace_geometry.origin.x, 
+            y: geometry.origin.y - workspace_geometry.origin.y 
+        }, 
+        geometry.size
+    );
+
+    Ok(Some((slot_ident, relative_geometry)))
+}
+
+/// Moves a floating window within its workspace
+pub fn move_floating(tree: &LayoutTree, element_ident: LayoutElemID, dx: i32, dy: i32) -> Result<(), String> {
+    if let Some(mut element) = tree.try_lookup_element(element_ident)? {
+        if let LayoutElementProfile::Window(ref mut window) = element.profile {
+            if let Some(ref mut relative_geometry) = window.floating {
+                relative_geometry.origin.x += dx;
+                relative_geometry.origin.y += dy;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Moves a window between the tiling of its workspace and the floating layer of it - toggles if 'floating' is none
+pub fn set_floating(wm_state: &mut WMState, element_ident: LayoutElemID, floating: Option<bool>) -> Result<String, String>{
+    let is_floating = match wm_state.tree.try_lookup_element(element_ident)? {
+        Some(element) => match element.profile {
+            LayoutElementProfile::Window(ref window) => window.floating.is_some(),
+            _ => return Err(String::from("Only windows can float."))
+        },
+        None => return Err(String::from("That element does not exist in the layout."))
+    };
+
+    let floating = floating.unwrap_or(!is_floating);
+    if floating == is_floating {
+        return Ok(format!("[{}] is already {}.", element_ident, if floating { "floating" } else { "tiled" }));
+    }
+
+    let workspace_ident = match workspace_of(&wm_state.tree, element_ident)? {
+        Some(workspace_ident) if workspace_ident != element_ident => workspace_ident,
+        _ => return Err(String::from("The window needs to be placed within a workspace."))
+    };
+
+    {
+        let tree = &mut wm_state.tree;
+
+        if floating {
+            let geometry = tree.geometry_of(element_ident)?.unwrap_or(Geometry::zero());
+
+            detach_element(tree, element_ident)?;
+
+            let (slot_ident, relative_geometry) = match seat_floating(tree, workspace_ident, geometry)? {
+                Some(seating) => seating,
+                None => return Err(String::from("The workspace has no floating layer."))
+            };
+
+            seat_element_in_slot(tree, element_ident, slot_ident)?;
+
+            if let Some(mut element) = tree.try_lookup_element(element_ident)? {
+                if let LayoutElementProfile::Window(ref mut window) = element.profile {
+                    window.floating = Some(relative_geometry);
+                }
+            }
+        }
+        else {
+            detach_element(tree, element_ident)?;
+
+            if let Some(mut element) = tree.try_lookup_element(element_ident)? {
+                if let LayoutElementProfile::Window(ref mut window) = element.profile {
+                    window.floating = None;
+                }
+            }
+
+            let mut layout_policy = tree.policy_of(workspace_ident);
+            let slot_ident = layout_policy.seat_window(tree, workspace_ident)?;
+            tree.set_policy_of(workspace_ident, layout_policy);
+
+            seat_element_in_slot(tree, element_ident, slot_ident)?;
+        }
+    }
+
+    LayoutTree::refresh(wm_state);
+
+    Ok(format!("[{}] is now {}.", element_ident, if floating { "floating" } else { "tiled" }))
+}
@@ -1,10 +1,16 @@
 #[macro_use] pub mod property;
 
 pub mod arrangement;
+pub mod navigation;
+pub mod floating;
+pub mod scratchpad;
+pub mod compaction;
 pub mod element;
 pub mod tag;
 pub mod policy;
+pub mod preselection;
 pub mod transition;
+pub mod snapshot;
 
 use std::cmp;
 use std::fmt;
@@ -25,6 +31,8 @@ use layout::element::padding::*;
 use layout::element::LayoutElementProfile;
 use layout::policy::LayoutPolicy;
 use layout::policy::auto_circulation::AutoCirculation;
+use layout::preselection::Preselection;
+use layout::snapshot::SessionSeat;
 use layout::property::PropertyBank;
 use layout::transition::Transition;
 use utils::geometry::PointExt;
@@ -41,8 +49,11 @@ pub struct LayoutTree{
     // the last focused layout element
     pub focused_id: LayoutElemID,
 
-    // the available workspaces of the layout 
-    elements: Vec<RefCell<LayoutElement>>,
+    // the elements of the layout, indexed by their identity - removed ones leave a gap until it is reused
+    elements: Vec<Option<RefCell<LayoutElement>>>,
+
+    // the identities of removed elements, handed out again before new ones
+    free_ids: Vec<LayoutElemID>,
 
     // the complete geometrical surface of all monitors 
     outer_geometry: Geometry,
@@ -50,7 +61,17 @@ pub struct LayoutTree{
     // tag register used to give names to layout elements  
     pub tags: TagRegister,
 
+    // the policy of workspaces that haven't been given one of their own
     pub layout_policy: Box<LayoutPolicy>,
+
+    // the policies of individual workspaces
+    pub workspace_policies: HashMap<LayoutElemID, Box<LayoutPolicy>>,
+
+    // where the next window is seated, if chosen in advance
+    pub preselection: Option<Preselection>,
+
+    // the places reserved for the windows of applications relaunched by a restored session
+    pub session_seats: Vec<SessionSeat>
 }
 
 impl LayoutTree {
@@ -59,53 +80,108 @@ impl LayoutTree {
             active_id: PARENT_ELEMENT   ,  
             focused_id: PARENT_ELEMENT,
             elements: Vec::new(),
+            free_ids: Vec::new(),
             tags: TagRegister::init(),
             outer_geometry: outer_geometry,
-            layout_policy: box AutoCirculation::init(grid_w * grid_h)
+            layout_policy: box AutoCirculation::init(grid_w * grid_h),
+            workspace_policies: HashMap::new(),
+            preselection: None,
+            session_seats: Vec::new()
         }
     }
 
     pub fn refresh(wm_state: &mut WMState){
         TagRegister::refresh_tag_statuses(wm_state);
 
-        let &mut WMState { ref tree, ref graphics_program, .. } = wm_state;
+        let &mut WMState { ref mut tree, ref graphics_program, .. } = wm_state;
         let mut stacked_padding: Option<u32> = None; 
         let mut stacked_scale = (1.0f32, 1.0f32);
-        arrangement::arrange(tree, PARENT_ELEMENT, wm_state.tree.outer_geometry, &mut stacked_padding, &mut stacked_scale, graphics_program.as_ref());
+        if let Err(msg) = arrangement::arrange(tree, PARENT_ELEMENT, tree.outer_geometry, &mut stacked_padding, &mut stacked_scale, graphics_program.as_ref()) {
+            println!("Couldn't arrange the layout: {}", msg);
+        }
+        if let Err(msg) = arrangement::arrange_expanded_windows(tree) {
+            println!("Couldn't arrange the expanded windows: {}", msg);
+        }
+
+        // Hint of where the next window goes
+        if let Some(program) = graphics_program.as_ref() {
+            let target_geometry = match tree.preselection.as_ref().map(|preselection| tree.geometry_of(preselection.target)) {
+                Some(Ok(geometry)) => geometry,
+                Some(Err(msg)) => {
+                    println!("Couldn't draw the preselection: {}", msg);
+                    None
+                },
+                None => None
+            };
+
+            if let (Some(target_geometry), Some(preselection)) = (target_geometry, tree.preselection.as_mut()) {
+                preselection.draw(program, target_geometry);
+            }
+        }
+    }
+
+    /// Whether a place is kept for a window to come - a named slot or a seat of the restored session
+    pub fn is_reserved(&self, element_ident: LayoutElemID) -> bool {
+        !self.tags.persistent_tags(element_ident).is_empty() || self.session_seats.iter().any(|seat| seat.slot_ident == element_ident)
     }
 
-    pub fn geometry_of(&self, element_ident: LayoutElemID) -> Option<Geometry> {
+    pub fn geometry_of(&self, element_ident: LayoutElemID) -> Result<Option<Geometry>, String> {
         let mut stacked_padding: Option<u32> = None; 
         let mut stacked_scale = (1.0f32, 1.0f32);
         arrangement::geometry_of(self, PARENT_ELEMENT, element_ident, self.outer_geometry, &mut stacked_padding, &mut stacked_scale)
     }
 
-    pub fn lookup_element(&self, elem_id: LayoutElemID) -> Option<RefMut<LayoutElement>>{   
-        let position = self.elements.iter().position(|element: &RefCell<LayoutElement>| {
-            match element.try_borrow(){
-                Ok(elem) => elem.element_id == elem_id,
-                _ => false
-            }
-        });
-        
-        match position{
-            Some(index) =>  unsafe { Some(self.elements.get_unchecked(index).borrow_mut()) },
+    /// A copy of the policy that a workspace is arranged by - it is written back with 'set_policy_of'
+    pub fn policy_of(&self, workspace_ident: LayoutElemID) -> Box<LayoutPolicy> {
+        match self.workspace_policies.get(&workspace_ident) {
+            Some(policy) => policy.clone(),
+            None => self.layout_policy.clone()
+        }
+    }
+
+    pub fn set_policy_of(&mut self, workspace_ident: LayoutElemID, policy: Box<LayoutPolicy>) {
+        self.workspace_policies.insert(workspace_ident, policy);
+    }
+
+    /// The index and identity of the workspace currently shown by the grid
+    pub fn active_workspace(&self) -> Result<Option<(usize, LayoutElemID)>, String> {
+        let grid_ident = match arrangement::find_grid(self, PARENT_ELEMENT)? {
+            Some(grid_ident) => grid_ident,
+            None => return Ok(None)
+        };
+
+        Ok(match self.try_lookup_element(grid_ident)? {
+            Some(element) => match element.profile {
+                LayoutElementProfile::Grid(ref grid) => Some((grid.active_subspace(), grid.get_active_child_id())),
+                _ => None
+            },
             None => None
+        })
+    }
+
+    /// An element of the layout, or None if there is no such element - an element that is already in use is an error
+    pub fn try_lookup_element(&self, elem_id: LayoutElemID) -> Result<Option<RefMut<LayoutElement>>, String>{
+        match self.elements.get(elem_id as usize) {
+            Some(&Some(ref element)) => match element.try_borrow_mut() {
+                Ok(element) => Ok(Some(element)),
+                Err(_) => Err(format!("Element [{}] is already borrowed!", elem_id))
+            },
+            _ => Ok(None)
         }
     }
 
-    pub fn lookup_element_by_tag(&self, tag: String) -> Vec<RefMut<LayoutElement>>{   
+    pub fn lookup_element_by_tag(&self, tag: String) -> Result<Vec<RefMut<LayoutElement>>, String>{   
         let mut element_references = Vec::<RefMut<LayoutElement>>::new();
         
         for elem_id in self.tags.address_element_by_tag(tag){
-            match self.lookup_element(elem_id)
+            match self.try_lookup_element(elem_id)?
             {
                 Some(element_ref) => { element_references.push(element_ref); },
                 None => {}
             };
         }
 
-        element_references
+        Ok(element_references)
     }
     
     pub fn lookup_element_from_view(&self, view: &View) -> Option<LayoutElemID>{
@@ -115,40 +191,82 @@ impl LayoutTree {
         }
     }
 
-    pub fn spawn_dummy_element(&mut self, parent_id: Option<LayoutElemID>) -> LayoutElemID{
-        self.elements.push(RefCell::new(LayoutElement::init_dummy(self.active_id, parent_id)));
+    /// A new unoccupied element - an error once every identity is in use, even after reclaiming the discarded ones
+    pub fn spawn_dummy_element(&mut self, parent_id: Option<LayoutElemID>) -> Result<LayoutElemID, String>{
+        if self.free_ids.is_empty() && self.active_id == LayoutElemID::max_value() {
+            self.reclaim_discarded();
+        }
+
+        let element_ident = match self.free_ids.pop() {
+            Some(recycled_ident) => recycled_ident,
+            None => match self.active_id.checked_add(1) {
+                Some(next_ident) => {
+                    self.active_id = next_ident;
+                    self.active_id - 1
+                },
+                None => return Err(String::from("Out of element identities, even after reclaiming the discarded ones!"))
+            }
+        };
+
+        let element = Some(RefCell::new(LayoutElement::init_dummy(element_ident, parent_id)));
 
-        self.active_id += 1;
-        return self.active_id - 1;
+        match self.elements.get_mut(element_ident as usize) {
+            Some(slot) => *slot = element,
+            None => self.elements.push(element)
+        }
+
+        Ok(element_ident)
+    }
+
+    /// Hands out the identities of discarded elements again (the ones left unoccupied without a parent) - a last resort short of a compaction
+    fn reclaim_discarded(&mut self) {
+        let discarded: Vec<LayoutElemID> = self.get_all_element_ids().into_iter()
+            .filter(|&element_ident| element_ident != PARENT_ELEMENT && match self.try_lookup_element(element_ident) {
+                Ok(Some(element)) => element.parent_id.is_none() && element.profile.is_none(),
+                // An element that is in use is anything but discarded
+                Ok(None) | Err(_) => false
+            })
+            .collect();
+
+        println!("NOTICE: Out of element identities - reclaiming {} discarded one(s).", discarded.len());
+
+        for element_ident in discarded {
+            self.remove_element(element_ident);
+        }
     }
 
     pub fn remove_view_binding_to(&mut self, element_ident: LayoutElemID) {
         self.tags.view_bindings.retain(|_, &mut v| v != element_ident);
+        self.tags.view_pid_bindings.retain(|_, &mut v| v != element_ident);
     } 
 
-    pub fn reserve_element_identity(&mut self, identity_to_reserv: LayoutElemID, profile: LayoutElementProfile) {
+    pub fn reserve_element_identity(&mut self, identity_to_reserv: LayoutElemID, profile: LayoutElementProfile) -> Result<(), String> {
         if let LayoutElementProfile::Window(ref window) = profile { 
             if let Some(ref view) = window.get_view(){
                 self.tags.view_bindings.insert(view.weak_reference(), identity_to_reserv); 
+                self.tags.view_pid_bindings.insert(view.pid(), identity_to_reserv); 
             }
         }
         
-        if let Some(ref mut element) = self.lookup_element(identity_to_reserv) {
+        if let Some(ref mut element) = self.try_lookup_element(identity_to_reserv)? {
             println!("Reserved identity [{}] for a '{}' element.", identity_to_reserv, profile);
             element.set_profile(profile);
         }
+
+        Ok(())
     }
 
-    pub fn swap_element_profile(&mut self, identity: LayoutElemID, new_profile: LayoutElementProfile) -> Option<LayoutElementProfile> {
+    pub fn swap_element_profile(&mut self, identity: LayoutElemID, new_profile: LayoutElementProfile) -> Result<Option<LayoutElementProfile>, String> {
         let mut old_profile = None; 
 
         if let LayoutElementProfile::Window(ref window) = new_profile { 
             if let Some(ref view) = window.get_view(){
                 self.tags.view_bindings.insert(view.weak_reference(), identity); 
+                self.tags.view_pid_bindings.insert(view.pid(), identity); 
             }
         }
 
-        if let Some(ref mut element) = self.lookup_element(identity) {
+        if let Some(ref mut element) = self.try_lookup_element(identity)? {
             old_profile = Some(element.profile.clone());
 
             element.set_profile(new_profile);
@@ -158,35 +276,130 @@ impl LayoutTree {
             if let &LayoutElementProfile::Window(ref window) = profile { 
                 if let Some(ref view) = window.get_view(){
                     self.tags.view_bindings.remove(&view.weak_reference()); 
+                    self.tags.view_pid_bindings.remove(&view.pid()); 
                 }
             }
         }
 
-        old_profile
+        Ok(old_profile)
     } 
 
-    pub fn root(&self) -> RefMut<LayoutElement>{
-        match self.lookup_element(PARENT_ELEMENT)
-        {
-            Some(parent) => parent,
-            None => { panic!("Root not found!"); }
+    /// Exchanges the contents of two elements which themselves stay in place - views and tags follow the contents
+    pub fn swap_elements(&mut self, elem1_ident: LayoutElemID, elem2_ident: LayoutElemID) -> Result<bool, String> {
+        if elem1_ident == elem2_ident || self.try_lookup_element(elem1_ident)?.is_none() || self.try_lookup_element(elem2_ident)?.is_none() {
+            return Ok(false);
         }
+
+        // The second element is emptied first so that its bindings aren't mistaken for the ones of the first 
+        let profile2 = self.swap_element_profile(elem2_ident, LayoutElementProfile::None)?.unwrap();
+        let profile1 = self.swap_element_profile(elem1_ident, profile2)?.unwrap();
+        self.swap_element_profile(elem2_ident, profile1)?;
+        self.tags.handle_element_swap(elem1_ident, elem2_ident);
+
+        Ok(true)
     }
 
-    pub fn parent_of(&self, element_ident: LayoutElemID) -> LayoutElemID {
-        self.
-            lookup_element(element_ident).expect("Element does not exist or is already borrowed!")
-            .parent_id.expect("Element does not have a parent!")
+    pub fn set_parent(&self, element_ident: LayoutElemID, parent_ident: Option<LayoutElemID>) -> Result<(), String> {
+        if let Some(mut element) = self.try_lookup_element(element_ident)? {
+            element.parent_id = parent_ident;
+        }
+
+        Ok(())
     }
 
-    pub fn get_all_element_ids(&self) -> Vec<LayoutElemID>{
-        let mut elements_ids = Vec::new();
+    /// Leaves an element that is no longer part of the layout without content, parent and tags
+    pub fn discard_element(&mut self, element_ident: LayoutElemID) -> Result<(), String> {
+        self.reserve_element_identity(element_ident, LayoutElementProfile::None)?;
+        self.remove_view_binding_to(element_ident);
+        self.set_parent(element_ident, None)?;
+        self.tags.untag_element(element_ident);
+
+        Ok(())
+    }
+
+    /// Removes an element that is no longer part of the layout for good - its identity is handed out again
+    pub fn remove_element(&mut self, element_ident: LayoutElemID) {
+        self.remove_view_binding_to(element_ident);
+        self.tags.untag_element(element_ident);
+        self.workspace_policies.remove(&element_ident);
+
+        let removed = match self.elements.get_mut(element_ident as usize) {
+            Some(slot) => slot.take().is_some(),
+            None => false
+        };
+
+        if removed {
+            self.free_ids.push(element_ident);
+        }
+    }
+
+    /// Whether 'element_ident' is 'ancestor_ident' or lies somewhere beneath it
+    pub fn is_descendant_of(&self, element_ident: LayoutElemID, ancestor_ident: LayoutElemID) -> Result<bool, String> {
+        let mut current = Some(element_ident);
+
+        while let Some(ident) = current {
+            if ident == ancestor_ident { 
+                return Ok(true); 
+            }
+
+            current = self.try_lookup_element(ident)?.and_then(|element| element.parent_id);
+        }
+
+        Ok(false)
+    }
+
+    /// The number of columns and rows of the grid
+    pub fn grid_dimensions(&self) -> Result<Option<(usize, usize)>, String> {
+        let grid_ident = match arrangement::find_grid(self, PARENT_ELEMENT)? {
+            Some(grid_ident) => grid_ident,
+            None => return Ok(None)
+        };
+
+        Ok(match self.try_lookup_element(grid_ident)? {
+            Some(element) => match element.profile {
+                LayoutElementProfile::Grid(ref grid) => Some((grid.columns(), grid.rows())),
+                _ => None
+            },
+            None => None
+        })
+    }
+
+    /// The position of a workspace in the grid
+    pub fn workspace_index(&self, workspace_ident: LayoutElemID) -> Result<Option<usize>, String> {
+        let grid_ident = match arrangement::find_grid(self, PARENT_ELEMENT)? {
+            Some(grid_ident) => grid_ident,
+            None => return Ok(None)
+        };
+
+        Ok(match self.try_lookup_element(grid_ident)? {
+            Some(element) => match element.profile {
+                LayoutElementProfile::Grid(ref grid) => grid.get_all_children().iter().position(|&e| e == workspace_ident),
+                _ => None
+            },
+            None => None
+        })
+    }
 
-        for element in self.elements.iter() {
-            elements_ids.push(element.borrow().element_id.clone())
+    pub fn root(&self) -> Result<RefMut<LayoutElement>, String>{
+        match self.try_lookup_element(PARENT_ELEMENT)?
+        {
+            Some(parent) => Ok(parent),
+            None => Err(String::from("Root not found!"))
+        }
+    }
+
+    pub fn parent_of(&self, element_ident: LayoutElemID) -> Result<LayoutElemID, String> {
+        match self.try_lookup_element(element_ident)? {
+            Some(element) => element.parent_id.ok_or(format!("Element [{}] does not have a parent!", element_ident)),
+            None => Err(format!("Element [{}] does not exist!", element_ident))
         }
+    }
 
-        elements_ids
+    pub fn get_all_element_ids(&self) -> Vec<LayoutElemID>{
+        self.elements.iter().enumerate()
+            .filter(|&(_, element)| element.is_some())
+            .map(|(index, _)| index as LayoutElemID)
+            .collect()
     }
 
     pub fn get_outer_geometry(&self) -> Geometry{
@@ -197,8 +410,8 @@ impl LayoutTree {
         self.outer_geometry = new_geometry;
     }
 
-    pub fn animate_property(&self, element_id: LayoutElemID, transitioning_property: &'static str, new_value: DefaultNumericType, relative_transition: bool, time_frame_ms: u64){
-        self.animate_property_after_delay(element_id, transitioning_property, new_value, relative_transition, time_frame_ms, 0);
+    pub fn animate_property(&self, element_id: LayoutElemID, transitioning_property: &'static str, new_value: DefaultNumericType, relative_transition: bool, time_frame_ms: u64) -> Result<(), String>{
+        self.animate_property_after_delay(element_id, transitioning_property, new_value, relative_transition, time_frame_ms, 0)
     }
 
     pub fn animate_element_property(&self, element: &mut LayoutElement, transitioning_property: &'static str, new_value: DefaultNumericType, relative_transition: bool, time_frame_ms: u64){
@@ -210,12 +423,15 @@ impl LayoutTree {
         }
     }
 
-    pub fn animate_property_after_delay(&self, element_id: LayoutElemID, transitioning_property: &'static str, new_value: DefaultNumericType, relative_transition: bool, time_frame_ms: u64, delay_ms: u64){
+    pub fn animate_property_after_delay(&self, element_id: LayoutElemID, transitioning_property: &'static str, new_value: DefaultNumericType, relative_transition: bool, time_frame_ms: u64, delay_ms: u64) -> Result<(), String>{
         assert!(time_frame_ms != 0u64, "Time frame can't be zero!");
         
         if let Ok(ref mut active_transitions) = ACTIVE_TRANSITIONS.try_lock(){   
-            let mut elem = self.lookup_element(element_id)
-                .expect("animate_property_after_delay: Either element identy does exist or it is already borrowed! Maybe use the explicit call instead?");
+            let mut elem = match self.try_lookup_element(element_id) {
+                Ok(Some(elem)) => elem,
+                Ok(None) => return Err(format!("animate_property_after_delay: Element [{}] does not exist!", element_id)),
+                Err(msg) => return Err(format!("animate_property_after_delay: {} Maybe use the explicit call instead?", msg))
+            };
                 
             if let Some(value_origin) = elem.get_property(transitioning_property){
                 active_transitions.push(Transition::new(element_id, transitioning_property, value_origin, new_value, relative_transition, time_frame_ms, delay_ms));
@@ -230,6 +446,8 @@ impl LayoutTree {
                 }
             }
         }   
+
+        Ok(())
     }
 
     pub fn animate_property_explicitly(&self, element_id: LayoutElemID, transitioning_property: &'static str, prev_value: DefaultNumericType, new_value: DefaultNumericType, relative_transition: bool, time_frame_ms: u64, delay_ms: u64){
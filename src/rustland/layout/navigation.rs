@@ -0,0 +1,291 @@
+use common::definitions::LayoutElemID;
+use common::event::Event;
+use io::event::broadcast;
+use layout::{LayoutTree, PARENT_ELEMENT};
+use layout::element::LayoutElementProfile;
+use layout::element::grid::Direction;
+use layout::arrangement::{workspace_of, find_grid, find_all_windows, find_first_unoccupied, reveal_place, detach_element, seat_element_in_slot, seat_element_beside};
+use layout::floating::is_floating;
+use wmstate::WMState;
+use utils::geometry::GeometryExt;
+
+use wlc::{Point, Geometry};
+
+/*
+    Moving windows (and containers) around the layout, swapping them and moving the focus between them
+*/
+
+pub fn move_element(wm_state: &mut WMState, carry: LayoutElemID, destination: LayoutElemID) -> Result<String, String>{
+    enum Seating { Slot(LayoutElemID), Workspace(LayoutElemID), Beside(LayoutElemID), Tab(LayoutElemID) }
+
+    let seating = {
+        let tree = &wm_state.tree;
+
+        match workspace_of(tree, carry)? {
+            Some(workspace_ident) if workspace_ident != carry => {},
+            _ => return Err(String::from("Only windows and the containers within workspaces can be moved."))
+        }
+
+        if tree.is_descendant_of(destination, carry)? {
+            return Err(String::from("An element can't be moved into itself."));
+        }
+
+        if tree.try_lookup_element(carry)?.and_then(|element| element.parent_id) == Some(destination) {
+            return Err(String::from("The element is already there."));
+        }
+
+        let (destination_is_unoccupied, destination_is_grid, destination_is_bisect, destination_is_tabbed) = match tree.try_lookup_element(destination)? {
+            Some(element) => match element.profile {
+                LayoutElementProfile::None => (true, false, false, false),
+                LayoutElementProfile::Grid(_) => (false, true, false, false),
+                LayoutElementProfile::Bisect(_) | LayoutElementProfile::Split(_) => (false, false, true, false),
+                LayoutElementProfile::Tabbed(_) => (false, false, false, true),
+                _ => (false, false, false, false)
+            },
+            None => return Err(String::from("Destination element missing in layout."))
+        };
+
+        // The grid holds workspaces only - which of them is meant can't be told
+        if destination_is_grid {
+            return Err(String::from("The grid itself can't hold an element, name one of its workspaces instead."));
+        }
+
+        match workspace_of(tree, destination)? {
+            Some(workspace_ident) if workspace_ident == destination => Seating::Workspace(destination),
+            Some(workspace_ident) if is_floating(tree, destination)? => Seating::Workspace(workspace_ident),
+            Some(_) if destination_is_unoccupied => Seating::Slot(destination),
+            Some(_) if destination_is_tabbed => Seating::Tab(destination),
+            Some(_) if destination_is_bisect => match find_first_unoccupied(tree, destination)? {
+                Some(slot_ident) => Seating::Slot(slot_ident),
+                None => Seating::Beside(destination)
+            },
+            Some(_) => Seating::Beside(destination),
+            None => return Err(String::from("The destination needs to be a workspace or a place within one."))
+        }
+    };
+
+    {
+        let tree = &mut wm_state.tree;
+
+        detach_element(tree, carry)?;
+
+        // Floating windows are tiled at their new place
+        if let Some(mut element) = tree.try_lookup_element(carry)? {
+            if let LayoutElementProfile::Window(ref mut window) = element.profile {
+                window.floating = None;
+            }
+        }
+
+        match seating {
+            Seating::Slot(slot_ident) => {
+                seat_element_in_slot(tree, carry, slot_ident)?;
+                reveal_place(tree, carry)?;
+            },
+            Seating::Workspace(workspace_ident) => {
+                let mut layout_policy = tree.policy_of(workspace_ident);
+                let slot_ident = layout_policy.seat_window(tree, workspace_ident)?;
+                tree.set_policy_of(workspace_ident, layout_policy);
+
+                seat_element_in_slot(tree, carry, slot_ident)?;
+            },
+            Seating::Beside(target_ident) => seat_element_beside(tree, carry, target_ident)?,
+            Seating::Tab(tabbed_ident) => {
+                if let Some(mut tabbed) = tree.try_lookup_element(tabbed_ident)? {
+                    if let LayoutElementProfile::Tabbed(ref mut tabbed) = tabbed.profile {
+                        tabbed.insert_child(carry);
+                    }
+                }
+
+                tree.set_parent(carry, Some(tabbed_ident))?;
+            }
+        }
+    }
+
+    LayoutTree::refresh(wm_state);
+
+    Ok(format!("Moved [{}] to [{}].", carry, destination))
+}
+
+/// Exchanges two windows - each one slides from its former place to the place of the other
+pub fn swap_windows(wm_state: &mut WMState, first: LayoutElemID, second: LayoutElemID) -> Result<String, String>{
+    let animation_time = 250;
+
+    for &element_ident in &[first, second] {
+        match wm_state.tree.try_lookup_element(element_ident)? {
+            Some(element) => match element.profile {
+                LayoutElementProfile::Window(_) => {},
+                _ => return Err(String::from("Only windows can be swapped."))
+            },
+            None => return Err(String::from("Window missing in layout."))
+        }
+    }
+
+    if first == second {
+        return Err(String::from("A window can't be swapped with itself."));
+    }
+
+    if is_floating(&wm_state.tree, first)? || is_floating(&wm_state.tree, second)? {
+        return Err(String::from("Floating windows can't be swapped."));
+    }
+
+    let (first_geometry, second_geometry) = match (wm_state.tree.geometry_of(first)?, wm_state.tree.geometry_of(second)?) {
+        (Some(first_geometry), Some(second_geometry)) => (first_geometry, second_geometry),
+        _ => return Err(String::from("The windows need to be placed within the layout."))
+    };
+
+    wm_state.tree.swap_elements(first, second)?;
+
+    // Start off at the former places
+    let (dx, dy) = ((second_geometry.origin.x - first_geometry.origin.x) as f32, (second_geometry.origin.y - first_geometry.origin.y) as f32);
+    wm_state.tree.animate_property_explicitly(first, "offset_x", dx, 0f32, false, animation_time, 0);
+    wm_state.tree.animate_property_explicitly(first, "offset_y", dy, 0f32, false, animation_time, 0);
+    wm_state.tree.animate_property_explicitly(second, "offset_x", -dx, 0f32, false, animation_time, 0);
+    wm_state.tree.animate_property_explicitly(second, "offset_y", -dy, 0f32, false, animation_time, 0);
+
+    LayoutTree::refresh(wm_state);
+
+    Ok(format!("Swapped [{}] with [{}].", first, second))
+}
+
+/// The candidate closest to 'origin' in a direction - judged by distance along the direction plus misalignment across it
+pub fn find_neighbour(tree: &LayoutTree, origin: Geometry, direction: Direction, candidates: &[LayoutElemID]) -> Result<Option<LayoutElemID>, String> {
+    let center = |g: &Geometry| (g.origin.x + g.size.w as i32 / 2, g.origin.y + g.size.h as i32 / 2);
+    let (origin_x, origin_y) = center(&origin);
+
+    let mut best: Option<(i32, LayoutElemID)> = None;
+
+    for &candidate_ident in candidates {
+        let geometry = match tree.geometry_of(candidate_ident)? {
+            Some(geometry) => geometry,
+            None => continue
+        };
+        let (x, y) = center(&geometry);
+
+        let score = match direction {
+            Direction::LEFT if x < origin.origin.x => (origin.origin.x - (geometry.origin.x + geometry.size.w as i32)).abs() + (y - origin_y).abs(),
+            Direction::RIGHT if x > origin.origin.x + origin.size.w as i32 => (geometry.origin.x - (origin.origin.x + origin.size.w as i32)).abs() + (y - origin_y).abs(),
+            Direction::UP if y < origin.origin.y => (origin.origin.y - (geometry.origin.y + geometry.size.h as i32)).abs() + (x - origin_x).abs(),
+            Direction::DOWN if y > origin.origin.y + origin.size.h as i32 => (geometry.origin.y - (origin.origin.y + origin.size.h as i32)).abs() + (x - origin_x).abs(),
+            _ => continue
+        };
+
+        if best.map_or(true, |(best_score, _)| score < best_score) {
+            best = Some((score, candidate_ident));
+        }
+    }
+
+    Ok(best.map(|(_, ident)| ident))
+}
+
+/// Moves the focus to the nearest window in a direction, optionally continuing into the adjacent workspace
+pub fn focus_in_direction(wm_state: &mut WMState, direction: Direction) -> Result<String, String> {
+    let (_, workspace_ident) = match wm_state.tree.active_workspace()? {
+        Some(active_workspace) => active_workspace,
+        None => return Err(String::from("There is no workspace to navigate in."))
+    };
+
+    // Windows in inactive tabs are out of sight and can't be navigated to
+    let mut windows = Vec::new();
+    find_all_windows(&mut windows, true, &wm_state.tree, workspace_ident)?;
+
+    let focused_ident = wm_state.tree.focused_id;
+    let focused_geometry = match windows.contains(&focused_ident) {
+        true => wm_state.tree.geometry_of(focused_ident)?,
+        false => None
+    };
+    let origin = match focused_geometry {
+        Some(geometry) => geometry,
+        None => match windows.first() {
+            // Nothing focused in this workspace - start from the first window
+            Some(&first_ident) => return focus_window(&wm_state.tree, first_ident),
+            None => wm_state.tree.geometry_of(workspace_ident)?.unwrap_or(Geometry::zero())
+        }
+    };
+
+    windows.retain(|&ident| ident != focused_ident);
+
+    if let Some(neighbour_ident) = find_neighbour(&wm_state.tree, origin, direction, &windows)? {
+        return focus_window(&wm_state.tree, neighbour_ident);
+    }
+
+    if !wm_state.config.layout.focus_wrapping {
+        return Err(String::from("There is no window in that direction."));
+    }
+
+    // Continue into the adjacent workspace of the grid:
+    let grid_ident = match find_grid(&wm_state.tree, PARENT_ELEMENT)? {
+        Some(grid_ident) => grid_ident,
+        None => return Err(String::from("There is no grid of workspaces to continue into."))
+    };
+    let switched = match wm_state.tree.try_lookup_element(grid_ident)? {
+        Some(mut element) => match element.profile {
+            LayoutElementProfile::Grid(ref mut grid) => {
+                let pre = grid.get_active_child_id();
+                grid.switch_to_subspace_in_direction(direction);
+
+                let post = grid.get_active_child_id();
+                if pre != post {
+                    broadcast(Event::Workspace { index: grid.active_subspace(), element_id: post });
+                    Some(post)
+                }
+                else { None }
+            },
+            _ => None
+        },
+        None => None
+    };
+
+    let new_workspace_ident = match switched {
+        Some(new_workspace_ident) => new_workspace_ident,
+        None => return Err(String::from("There is neither a window nor a workspace in that direction."))
+    };
+
+    wm_state.jump_to_active_workspace(300);
+
+    // Pretend to come from right outside the opposite edge of the new workspace
+    let mut new_windows = Vec::new();
+    find_all_windows(&mut new_windows, true, &wm_state.tree, new_workspace_ident)?;
+
+    let (old_area, new_area) = match (wm_state.tree.geometry_of(workspace_ident)?, wm_state.tree.geometry_of(new_workspace_ident)?) {
+        (Some(old_area), Some(new_area)) => (old_area, new_area),
+        _ => return Ok(String::from("Switched workspace."))
+    };
+
+    let mut entry = Geometry::new(
+        Point { 
+            x: origin.origin.x - old_area.origin.x + new_area.origin.x, 
+            y: origin.origin.y - old_area.origin.y + new_area.origin.y 
+        }, 
+        origin.size
+    );
+
+    match direction {
+        Direction::LEFT => entry.origin.x = new_area.origin.x + new_area.size.w as i32,
+        Direction::RIGHT => entry.origin.x = new_area.origin.x - entry.size.w as i32,
+        Direction::UP => entry.origin.y = new_area.origin.y + new_area.size.h as i32,
+        Direction::DOWN => entry.origin.y = new_area.origin.y - entry.size.h as i32
+    }
+
+    match find_neighbour(&wm_state.tree, entry, direction, &new_windows)? {
+        Some(neighbour_ident) => focus_window(&wm_state.tree, neighbour_ident),
+        None => Ok(String::from("Switched to an empty workspace."))
+    }
+}
+
+/// Hands the keyboard focus to the view of a window - the compositor is notified through the focus callback
+pub fn focus_window(tree: &LayoutTree, element_ident: LayoutElemID) -> Result<String, String> {
+    match tree.try_lookup_element(element_ident)? {
+        Some(element) => match element.profile {
+            LayoutElementProfile::Window(ref window) => match window.get_view() {
+                Some(view) => {
+                    view.bring_to_front();
+                    view.focus();
+                    Ok(format!("Focused [{}].", element_ident))
+                },
+                None => Err(String::from("The window has no view to focus."))
+            },
+            _ => Err(String::from("Only windows can be focused."))
+        },
+        None => Err(String::from("That element does not exist in the layout."))
+    }
+}
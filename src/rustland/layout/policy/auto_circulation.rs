@@ -1,8 +1,8 @@
-use std::ops::DerefMut;
+use std::cmp::min;
 
 use common::definitions::LayoutElemID;
 use layout::{LayoutTree, PARENT_ELEMENT};
-use layout::arrangement:: {find_first_unoccupied, find_all_windows};
+use layout::arrangement:: {find_first_unoccupied, find_all_windows, find_reserved_places, reveal_place, detach_element};
 use layout::element::{LayoutElement, LayoutElementProfile};
 use layout::element::bisect::{Orientation, Bisect, Side};
 use layout::policy::*;
@@ -34,33 +34,41 @@ impl AutoCirculation{
 }
 
 impl LayoutPolicy for AutoCirculation{
-    fn seat_window(&mut self, mut tree: &mut LayoutTree) -> LayoutElemID {
-        if let Some(unoccupied_id) = find_first_unoccupied(&tree, PARENT_ELEMENT){
+    fn name(&self) -> &'static str {
+        "auto_circulation"
+    }
+
+    fn seat_window(&mut self, mut tree: &mut LayoutTree, workspace_ident: LayoutElemID) -> Result<LayoutElemID, String> {
+        if let Some(unoccupied_id) = find_first_unoccupied(&tree, workspace_ident)? {
             // Unoccupied spots preexisting in the layout makes this easy: 
-            let parent_ident = tree.parent_of(unoccupied_id);
-            tree.animate_property(parent_ident, "gap_size", 7f32, false, 125);    
+            let parent_ident = tree.parent_of(unoccupied_id)?;
+            tree.animate_property(parent_ident, "gap_size", 7f32, false, 125)?;    
+            reveal_place(tree, unoccupied_id)?;
             
             
-            unoccupied_id
+            Ok(unoccupied_id)
         }
         else{
             // Otherwise, we have to extend the layout with new spots:
             println!("NOTICE: Extending the layout structure!");
 
             let mut active_windows: Vec<LayoutElemID> = Vec::new();
-            find_all_windows(&mut active_windows, true, tree, PARENT_ELEMENT);
+            find_all_windows(&mut active_windows, false, tree, workspace_ident)?;
+
+            // A workspace of nothing but reserved places makes room beside one of them
+            if active_windows.is_empty() {
+                find_reserved_places(&mut active_windows, tree, workspace_ident)?;
+            }
 
             if let Some(&last_id) = active_windows.last() {
-                let current_workspace = {
-                    let mut element = tree.lookup_element(2).unwrap();
-                    
-                    match element.profile{
-                        LayoutElementProfile::Grid(ref mut grid) => grid.active_subspace(),
-                        _ => { panic!("Expected element to be a workspace.") }        
-                    }
-                };
+                let current_workspace = tree.workspace_index(workspace_ident)?.unwrap_or(0);
+
+                // The policy may have been set up for fewer workspaces than the grid has by now
+                if current_workspace >= self.last_orientations.len() {
+                    self.last_orientations.resize(current_workspace + 1, Orientation::Horizontal);
+                }
 
-                let (_, extension) = Bisect::init(last_id, tree, self.last_orientations[current_workspace], 0.001f32);
+                let (_, extension) = Bisect::init(last_id, tree, self.last_orientations[current_workspace], 0.001f32)?;
                 self.last_orientations[current_workspace] = self.last_orientations[current_workspace].opposite();
 
                 let (new_preoccupied_id, new_unoccupied_id) = {
@@ -71,52 +79,68 @@ impl LayoutPolicy for AutoCirculation{
 
                 // update tags according to element swap
                 tree.tags.handle_element_swap(last_id, new_preoccupied_id);
+                for seat in tree.session_seats.iter_mut() {
+                    if seat.slot_ident == last_id {
+                        seat.slot_ident = new_preoccupied_id;
+                    }
+                }
 
-                if let Some(thrown_out_profile) = tree.swap_element_profile(last_id, LayoutElementProfile::Bisect(extension)){
-                    tree.reserve_element_identity(new_preoccupied_id, thrown_out_profile);
-                    tree.animate_property(last_id, "ratio", 0.5f32, false, 125);
-                    new_unoccupied_id
+                if let Some(thrown_out_profile) = tree.swap_element_profile(last_id, LayoutElementProfile::Bisect(extension))?{
+                    tree.reserve_element_identity(new_preoccupied_id, thrown_out_profile)?;
+                    tree.animate_property(last_id, "ratio", 0.5f32, false, 125)?;
+                    Ok(new_unoccupied_id)
                 }
                 else {
-                    panic!("ERROR: No space in layout found!");
+                    Err(String::from("No space in layout found!"))
                 }
             }
             else{
-                panic!("Last index did not exist!");
+                Err(String::from("Last index did not exist!"))
             }
         }
     }
 
-    fn decorate_window(&mut self, wm_state: &mut RwLockWriteGuard<WMState>, element_ident: LayoutElemID) {
-        let &mut WMState{ref tree, ref mut graphics_program, ..} = wm_state.deref_mut();
-       
-        if let Some(mut element) = tree.lookup_element(element_ident){
-            match element.profile{
-                LayoutElementProfile::Window(ref mut window) => {
-                    if let &mut Some(ref mut program) = graphics_program{
-                        window.apply_frame(element_ident, program, 0f32);
-                        tree.animate_property_after_delay(element_ident, "frame_opacity", 0.7f32, false, 500, 200);
-                    }
-                },
-                _ => {}
-            }
+    fn detach_window(&mut self, tree: &mut LayoutTree, element_ident: LayoutElemID) -> Result<Option<WeakView>, String>{
+        let weak_view = match tree.try_lookup_element(element_ident)? {
+            Some(mut element) => match element.profile {
+                LayoutElementProfile::Window(ref mut window) => window.detach_view(),
+                _ => None
+            },
+            None => None
+        };
+
+        let parent_is_bisect = match tree.try_lookup_element(tree.parent_of(element_ident)?)? {
+            Some(parent) => match parent.profile {
+                LayoutElementProfile::Bisect(_) => true,
+                _ => false
+            },
+            None => false
+        };
+
+        if !parent_is_bisect {
+            // Tabs, splits (and paddings) close the gap on their own terms
+            detach_element(tree, element_ident)?;
+            tree.discard_element(element_ident)?;
+
+            return Ok(weak_view);
         }
-    }
 
-    fn detach_window(&mut self, tree: &mut LayoutTree, element_ident: LayoutElemID) -> Option<WeakView>{
-        tree.reserve_element_identity(element_ident, LayoutElementProfile::None);
+        tree.reserve_element_identity(element_ident, LayoutElementProfile::None)?;
         
         let (bisect_parent_ident, bisect_removal, child_side) = {
-            let parent_ident = tree.parent_of(element_ident);
+            let parent_ident = tree.parent_of(element_ident)?;
             
-            if let LayoutElementProfile::Bisect(ref bisect) = tree.lookup_element(parent_ident).unwrap().profile {
-                let side = bisect.child_side(element_ident);
-                let missing_adjacent_element = bisect.count_active_children(tree) == 0;
-                
-                (Some(parent_ident), missing_adjacent_element, side)      
-            } 
-            else { 
-                (None, false, Side::Neither)
+            match tree.try_lookup_element(parent_ident)? {
+                Some(parent) => match parent.profile {
+                    LayoutElementProfile::Bisect(ref bisect) => {
+                        let side = bisect.child_side(element_ident);
+                        let missing_adjacent_element = bisect.count_active_children(tree)? == 0;
+                        
+                        (Some(parent_ident), missing_adjacent_element, side)      
+                    },
+                    _ => (None, false, Side::Neither)
+                },
+                None => (None, false, Side::Neither)
             }
         };
 
@@ -127,8 +151,8 @@ impl LayoutPolicy for AutoCirculation{
 
             if bisect_removal {
                 // No elements left in bisect - it can go away:
-                let grand_parent_ident = tree.parent_of(parent_ident);
-                if let Some(mut grand_parent) = tree.lookup_element(grand_parent_ident){
+                let grand_parent_ident = tree.parent_of(parent_ident)?;
+                if let Some(mut grand_parent) = tree.try_lookup_element(grand_parent_ident)?{
                     
                     let (property, new_value) = match grand_parent.profile {
                         LayoutElementProfile::Bisect(ref bisect) => ("ratio", 1.0f32),
@@ -138,7 +162,7 @@ impl LayoutPolicy for AutoCirculation{
                     if property != "" { tree.animate_element_property(&mut grand_parent, property, new_value, false, time_frame); }
                 }
                 
-                tree.reserve_element_identity(parent_ident, LayoutElementProfile::None);
+                tree.reserve_element_identity(parent_ident, LayoutElementProfile::None)?;
             }
             else{
                 // One element left in bisect - give it full space:
@@ -148,18 +172,26 @@ impl LayoutPolicy for AutoCirculation{
                         Side::Right => 1.0,
                         Side::Neither => panic!()
                     }
-                , false, time_frame);            
+                , false, time_frame)?;            
             }
         }
 
-        if let Some(mut element) = tree.lookup_element(element_ident){
-            match element.profile{
-                LayoutElementProfile::Window(ref mut window) => window.detach_view(),
-                _ => None
-            }
+        Ok(weak_view)
+    }
+
+    fn workspaces_inserted(&mut self, indices: &[usize]) {
+        // Ascending indices, so every insertion lands on its final position
+        for &index in indices {
+            let position = min(index, self.last_orientations.len());
+            self.last_orientations.insert(position, Orientation::Horizontal);
         }
-        else {
-            None
+    }
+
+    fn workspaces_removed(&mut self, indices: &[usize]) {
+        for &index in indices.iter().rev() {
+            if index < self.last_orientations.len() {
+                self.last_orientations.remove(index);
+            }
         }
     }
 
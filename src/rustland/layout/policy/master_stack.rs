@@ -0,0 +1,258 @@
+use std::cmp::{min, max};
+
+use common::definitions::LayoutElemID;
+use layout::LayoutTree;
+use layout::arrangement::{workspace_of, detach_element, seat_element_in_slot, split_evenly, discard_containers};
+use layout::floating::is_floating;
+use layout::element::LayoutElementProfile;
+use layout::element::bisect::{Orientation, Bisect, MIN_RATIO, MAX_RATIO};
+use layout::policy::*;
+
+/*
+    Generates a layout with a number of master windows in the former area, and the rest stacked evenly in the latter.
+
+    Only the bisect and splits built by the policy itself (its skeleton) are rebuilt, whatever else is placed 
+    in it - windows as well as tabs, splits or reserved places - is moved around as a whole.
+*/
+
+#[derive(Clone)]
+pub struct MasterStack{
+    master_count: usize,
+    master_ratio: f32,
+
+    // the elements that the policy has built the tiling of its workspace from
+    skeleton: Vec<LayoutElemID>
+}
+
+impl MasterStack{
+    pub fn init(master_count: usize, master_ratio: f32) -> MasterStack{
+        MasterStack{
+            master_count: master_count,
+            master_ratio: master_ratio.max(MIN_RATIO).min(MAX_RATIO),
+            skeleton: Vec::new()
+        }
+    }
+
+    fn is_skeleton(&self, tree: &LayoutTree, element_ident: LayoutElemID) -> Result<bool, String> {
+        // An identity handed out again since doesn't count
+        if !self.skeleton.contains(&element_ident) {
+            return Ok(false);
+        }
+
+        Ok(tree.try_lookup_element(element_ident)?.map_or(false, |element| match element.profile {
+            LayoutElementProfile::Bisect(_) | LayoutElementProfile::Split(_) | LayoutElementProfile::None => true,
+            _ => false
+        }))
+    }
+
+    /// What the policy arranges in a workspace (windows and foreign containers) - masters first, in the order they are placed
+    fn tiled_units(&self, tree: &LayoutTree, workspace_ident: LayoutElemID) -> Result<Vec<LayoutElemID>, String> {
+        let mut units = Vec::new();
+
+        let root_ident = match tree.try_lookup_element(workspace_ident)? {
+            Some(workspace) => match workspace.profile {
+                LayoutElementProfile::Padding(ref padding) => Some(padding.child_elem_id),
+                _ => None
+            },
+            None => None
+        };
+
+        if let Some(root_ident) = root_ident {
+            self.find_units(&mut units, tree, root_ident, true)?;
+        }
+
+        Ok(units)
+    }
+
+    fn find_units(&self, units: &mut Vec<LayoutElemID>, tree: &LayoutTree, element_ident: LayoutElemID, is_root: bool) -> Result<(), String> {
+        let (is_none, child_ids) = match tree.try_lookup_element(element_ident)? {
+            Some(element) => (element.profile.is_none(), element.profile.children()),
+            None => return Ok(())
+        };
+
+        if self.is_skeleton(tree, element_ident)? {
+            for child_ident in child_ids {
+                // Recursion
+                self.find_units(units, tree, child_ident, false)?;
+            }
+        }
+        // The placeholder of an empty workspace is no place of its own
+        else if !(is_none && is_root) {
+            units.push(element_ident);
+        }
+
+        Ok(())
+    }
+
+    /// Rebuilds the skeleton of a workspace around 'units', optionally with an unoccupied place last
+    fn restructure(&mut self, tree: &mut LayoutTree, workspace_ident: LayoutElemID, units: &[LayoutElemID], with_unoccupied: bool) -> Result<Option<LayoutElemID>, String> {
+        let former_root_ident = match tree.try_lookup_element(workspace_ident)? {
+            Some(workspace) => match workspace.profile {
+                LayoutElementProfile::Padding(ref padding) => padding.child_elem_id,
+                _ => return Ok(None)
+            },
+            None => return Ok(None)
+        };
+
+        // Stops at the units, so only the former skeleton goes
+        discard_containers(tree, former_root_ident, units)?;
+
+        let count = units.len() + if with_unoccupied { 1 } else { 0 };
+        let masters = min(self.master_count, count);
+
+        let root_ident = tree.spawn_dummy_element(Some(workspace_ident))?;
+        let mut slots = Vec::new();
+        let mut skeleton = vec![root_ident];
+
+        if masters == 0 || masters == count {
+            split_evenly(tree, root_ident, count, Orientation::Vertical, &mut slots)?;
+        }
+        else {
+            let (_, split) = Bisect::init(root_ident, tree, Orientation::Horizontal, self.master_ratio)?;
+            let (master_ident, stack_ident) = {
+                let mut iter = split.children_iter().cloned();
+                (iter.next().unwrap(), iter.next().unwrap())
+            };
+
+            tree.reserve_element_identity(root_ident, LayoutElementProfile::Bisect(split))?;
+            skeleton.push(master_ident);
+            skeleton.push(stack_ident);
+
+            split_evenly(tree, master_ident, masters, Orientation::Vertical, &mut slots)?;
+            split_evenly(tree, stack_ident, count - masters, Orientation::Vertical, &mut slots)?;
+        }
+
+        if let Some(mut workspace) = tree.try_lookup_element(workspace_ident)? {
+            workspace.profile.replace_child(former_root_ident, root_ident);
+        }
+
+        for (&unit_ident, &slot_ident) in units.iter().zip(slots.iter()) {
+            seat_element_in_slot(tree, unit_ident, slot_ident)?;
+        }
+
+        // The slots that were seated in are gone
+        skeleton.extend(slots.iter().skip(units.len()).cloned());
+        skeleton.retain(|element_ident| !slots[..min(units.len(), slots.len())].contains(element_ident));
+        self.skeleton = skeleton;
+
+        match with_unoccupied {
+            true => Ok(slots.last().cloned()),
+            false => Ok(None)
+        }
+    }
+}
+
+/// Parses either an absolute value or one relative to 'current' (prefixed with + or -)
+fn adjusted(current: f32, arg: &str) -> Option<f32> {
+    match arg.parse::<f32>() {
+        Ok(value) if arg.starts_with('+') || arg.starts_with('-') => Some(current + value),
+        Ok(value) => Some(value),
+        Err(_) => None
+    }
+}
+
+impl LayoutPolicy for MasterStack{
+    fn name(&self) -> &'static str {
+        "master_stack"
+    }
+
+    fn seat_window(&mut self, tree: &mut LayoutTree, workspace_ident: LayoutElemID) -> Result<LayoutElemID, String> {
+        let units = self.tiled_units(tree, workspace_ident)?;
+
+        match self.restructure(tree, workspace_ident, &units, true)? {
+            Some(slot_ident) => Ok(slot_ident),
+            None => Err(String::from("Expected the workspace to be a padding."))
+        }
+    }
+
+    fn detach_window(&mut self, tree: &mut LayoutTree, element_ident: LayoutElemID) -> Result<Option<WeakView>, String>{
+        let workspace_ident = workspace_of(tree, element_ident)?;
+        let floating = is_floating(tree, element_ident)?;
+
+        let weak_view = match tree.try_lookup_element(element_ident)? {
+            Some(mut element) => match element.profile {
+                LayoutElementProfile::Window(ref mut window) => window.detach_view(),
+                _ => None
+            },
+            None => None
+        };
+
+        let parent_ident = tree.parent_of(element_ident)?;
+
+        if !floating && (Some(parent_ident) == workspace_ident || self.is_skeleton(tree, parent_ident)?) {
+            // No longer a window, its place goes away along with the former skeleton
+            tree.reserve_element_identity(element_ident, LayoutElementProfile::None)?;
+            self.skeleton.push(element_ident);
+        }
+        else {
+            // The floating layer or a foreign container closes the gap on its own terms
+            detach_element(tree, element_ident)?;
+            tree.discard_element(element_ident)?;
+        }
+
+        if let Some(workspace_ident) = workspace_ident {
+            let units = self.tiled_units(tree, workspace_ident)?;
+            self.restructure(tree, workspace_ident, &units, false)?;
+        }
+
+        Ok(weak_view)
+    }
+
+    fn command(&mut self, tree: &mut LayoutTree, workspace_ident: LayoutElemID, element_ident: LayoutElemID, args: &[String]) -> Result<String, String> {
+        let mut units = self.tiled_units(tree, workspace_ident)?;
+
+        // A window within a foreign container is promoted along with the container
+        let mut promoted = None;
+        for (position, &unit_ident) in units.iter().enumerate() {
+            if tree.is_descendant_of(element_ident, unit_ident)? {
+                promoted = Some(position);
+                break;
+            }
+        }
+
+        let result = match (args.get(0).map(|arg| arg.as_str()), args.get(1)) {
+            (Some("promote"), _) => match promoted {
+                // The first master gives way to the next one
+                Some(0) if units.len() > 1 => {
+                    units.swap(0, 1);
+                    Ok(format!("[{}] gave way as master.", element_ident))
+                },
+                Some(position) => {
+                    let unit_ident = units.remove(position);
+                    units.insert(0, unit_ident);
+                    Ok(format!("[{}] is now master.", element_ident))
+                },
+                None => Err(String::from("Only tiled windows of the workspace can be promoted."))
+            },
+            (Some("count"), Some(arg)) => match adjusted(self.master_count as f32, arg) {
+                Some(count) => {
+                    self.master_count = max(0, count.round() as i32) as usize;
+                    Ok(format!("The master area holds {} window(s).", self.master_count))
+                },
+                None => Err(format!("Invalid count: {}", arg))
+            },
+            (Some("ratio"), Some(arg)) => match adjusted(self.master_ratio, arg) {
+                Some(ratio) => {
+                    self.master_ratio = ratio.max(MIN_RATIO).min(MAX_RATIO);
+                    Ok(format!("The master area takes {:.2} of the workspace.", self.master_ratio))
+                },
+                None => Err(format!("Invalid ratio: {}", arg))
+            },
+            _ => Err(String::from("Usage: [@WINDOW] master promote | master count [+|-]<n> | master ratio [+|-]<ratio>"))
+        };
+
+        if result.is_ok() {
+            self.restructure(tree, workspace_ident, &units, false)?;
+        }
+
+        result
+    }
+
+    fn workspaces_inserted(&mut self, _indices: &[usize]) {}
+
+    fn workspaces_removed(&mut self, _indices: &[usize]) {}
+
+    fn box_clone(&self) -> Box<LayoutPolicy> {
+        Box::new((*self).clone())
+    }
+}
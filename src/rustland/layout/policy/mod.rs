@@ -1,7 +1,9 @@
 use std::sync::RwLockWriteGuard;
+use std::ops::DerefMut;
 
 use common::definitions::{LayoutElemID};
 use layout::LayoutTree;
+use layout::element::LayoutElementProfile;
 use layout::element::window::Window;
 use sugars::program::GraphicsProgram;
 use wmstate::WMState;
@@ -10,12 +12,51 @@ use gl::types::GLuint;
 use wlc::WeakView;
 
 pub mod auto_circulation;
+pub mod master_stack;
 
 pub trait LayoutPolicy{
-    fn seat_window(&mut self, tree: &mut LayoutTree) -> LayoutElemID;
-    fn decorate_window(&mut self, wm_state: &mut RwLockWriteGuard<WMState>, element_ident: LayoutElemID);
+    // The name that the policy goes by in the config and in commands
+    fn name(&self) -> &'static str;
+
+    fn seat_window(&mut self, tree: &mut LayoutTree, workspace_ident: LayoutElemID) -> Result<LayoutElemID, String>;
+
+    // Frames a window that has just been seated, and fades the frame in
+    fn decorate_window(&mut self, wm_state: &mut RwLockWriteGuard<WMState>, element_ident: LayoutElemID) -> Result<(), String> {
+        let &mut WMState{ref tree, ref mut graphics_program, ..} = wm_state.deref_mut();
+
+        let framed = match tree.try_lookup_element(element_ident)?{
+            Some(mut element) => match element.profile{
+                LayoutElementProfile::Window(ref mut window) => {
+                    if let &mut Some(ref mut program) = graphics_program{
+                        window.apply_frame(element_ident, program, 0f32);
+                        true
+                    }
+                    else { false }
+                },
+                _ => false
+            },
+            None => false
+        };
+
+        // The window is no longer borrowed once its frame is animated
+        if framed {
+            tree.animate_property_after_delay(element_ident, "frame_opacity", 0.7f32, false, 500, 200)?;
+        }
+
+        Ok(())
+    }
+
     fn box_clone(&self) -> Box<LayoutPolicy>;
-    fn detach_window(&mut self, tree: &mut LayoutTree, element_ident: LayoutElemID) -> Option<WeakView>;
+    fn detach_window(&mut self, tree: &mut LayoutTree, element_ident: LayoutElemID) -> Result<Option<WeakView>, String>;
+
+    // Policy specific commands, issued for an element of a workspace
+    fn command(&mut self, _tree: &mut LayoutTree, _workspace_ident: LayoutElemID, _element_ident: LayoutElemID, _args: &[String]) -> Result<String, String> {
+        Err(String::from("The layout policy in use doesn't take any commands."))
+    }
+
+    // Notifications for keeping per workspace state in step with the indices of the grid
+    fn workspaces_inserted(&mut self, indices: &[usize]);
+    fn workspaces_removed(&mut self, indices: &[usize]);
 }
 
 impl Clone for Box<LayoutPolicy>{
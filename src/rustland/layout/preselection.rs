@@ -0,0 +1,74 @@
+use common::definitions::LayoutElemID;
+use layout::element::grid::Direction;
+use layout::element::bisect::Orientation;
+use sugars::program::GraphicsProgram;
+use sugars::Renderable;
+use sugars::frame::Frame;
+
+use wlc::{Geometry, Point, Size};
+
+/*
+    A preselected place next to a window where the next spawned window is seated (as in bspwm)
+*/
+
+pub struct Preselection{
+    // the window to split
+    pub target: LayoutElemID,
+
+    // the side of the target that the next window goes to
+    pub direction: Direction,
+
+    // the share of the target space given to the next window
+    pub ratio: f32,
+
+    frame: Option<Frame>
+}
+
+impl Preselection{
+    pub fn new(target: LayoutElemID, direction: Direction, ratio: f32, graphics_program: Option<&GraphicsProgram>) -> Preselection{
+        Preselection{
+            target: target,
+            direction: direction,
+            ratio: ratio,
+            frame: graphics_program.map(|program| Frame::new(program.id, 0.3f32))
+        }
+    }
+
+    pub fn orientation(&self) -> Orientation{
+        match self.direction {
+            Direction::LEFT | Direction::RIGHT => Orientation::Horizontal,
+            Direction::UP | Direction::DOWN => Orientation::Vertical
+        }
+    }
+
+    /// Whether the next window becomes the former child of the split
+    pub fn goes_first(&self) -> bool{
+        match self.direction {
+            Direction::LEFT | Direction::UP => true,
+            Direction::RIGHT | Direction::DOWN => false
+        }
+    }
+
+    /// The part of the target geometry that the next window will occupy
+    pub fn hint_geometry(&self, target_geometry: Geometry) -> Geometry{
+        let w = (target_geometry.size.w as f32 * self.ratio) as u32;
+        let h = (target_geometry.size.h as f32 * self.ratio) as u32;
+        let origin = target_geometry.origin;
+        let size = target_geometry.size;
+
+        match self.direction {
+            Direction::LEFT => Geometry::new(origin, Size{ w: w, h: size.h }),
+            Direction::RIGHT => Geometry::new(Point{ x: origin.x + (size.w - w) as i32, y: origin.y }, Size{ w: w, h: size.h }),
+            Direction::UP => Geometry::new(origin, Size{ w: size.w, h: h }),
+            Direction::DOWN => Geometry::new(Point{ x: origin.x, y: origin.y + (size.h - h) as i32 }, Size{ w: size.w, h: h })
+        }
+    }
+
+    pub fn draw(&mut self, program: &GraphicsProgram, target_geometry: Geometry){
+        let hint = self.hint_geometry(target_geometry);
+
+        if let Some(ref mut frame) = self.frame {
+            frame.draw(program, hint);
+        }
+    }
+}
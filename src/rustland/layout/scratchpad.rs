@@ -0,0 +1,145 @@
+use common::definitions::LayoutElemID;
+use layout::LayoutTree;
+use layout::element::LayoutElementProfile;
+use layout::element::window::WindowMode;
+use layout::arrangement::{workspace_of, detach_element, seat_element_in_slot};
+use layout::floating::seat_floating;
+use layout::navigation::focus_window;
+use wmstate::WMState;
+use utils::geometry::{PointExt, SizeExt, GeometryExt};
+
+use wlc::{Point, Size, Geometry, Visibility};
+
+/*
+    The scratchpad keeps windows out of sight until they are summoned, floating, to the active workspace
+*/
+
+pub fn find_scratchpad(wm_state: &WMState) -> Option<LayoutElemID> {
+    wm_state.tree.tags.address_element_by_tag(wm_state.config.layout.scratchpad_tag.clone()).first().cloned()
+}
+
+/// Moves a window out of sight into the scratchpad
+pub fn hide_in_scratchpad(wm_state: &mut WMState, element_ident: LayoutElemID) -> Result<String, String>{
+    let scratchpad_ident = match find_scratchpad(wm_state) {
+        Some(scratchpad_ident) => scratchpad_ident,
+        None => return Err(String::from("There is no scratchpad in the layout."))
+    };
+
+    let parent_ident = match wm_state.tree.try_lookup_element(element_ident)? {
+        Some(element) => match element.profile {
+            LayoutElementProfile::Window(_) => element.parent_id,
+            _ => return Err(String::from("Only windows can be put in the scratchpad."))
+        },
+        None => return Err(String::from("That element does not exist in the layout."))
+    };
+
+    if parent_ident == Some(scratchpad_ident) {
+        return Ok(format!("[{}] is already hidden in the scratchpad.", element_ident));
+    }
+
+    let size = wm_state.tree.geometry_of(element_ident)?.map(|geometry| geometry.size).unwrap_or(Size::zero());
+
+    {
+        let tree = &mut wm_state.tree;
+        
+        detach_element(tree, element_ident)?;
+
+        if let Some(mut scratchpad) = tree.try_lookup_element(scratchpad_ident)? {
+            if let LayoutElementProfile::Padding(ref mut padding) = scratchpad.profile {
+                padding.floating.push(element_ident);
+            }
+        }
+
+        tree.set_parent(element_ident, Some(scratchpad_ident))?;
+
+        if let Some(mut element) = tree.try_lookup_element(element_ident)? {
+            if let LayoutElementProfile::Window(ref mut window) = element.profile {
+                window.floating = Some(Geometry::new(Point::origin(), size));
+                window.set_mode(WindowMode::Tiled);
+                window.expansion = 0f32;
+
+                if let Some(view) = window.get_view() {
+                    view.set_visibility(Visibility::Null);
+                }
+            }
+        }
+    }
+
+    LayoutTree::refresh(wm_state);
+
+    Ok(format!("Hid [{}] in the scratchpad.", element_ident))
+}
+
+/// Brings a window from the scratchpad to the active workspace, floating in the center of it
+pub fn show_from_scratchpad(wm_state: &mut WMState, element_ident: LayoutElemID) -> Result<String, String>{
+    let scratchpad_ident = match find_scratchpad(wm_state) {
+        Some(scratchpad_ident) => scratchpad_ident,
+        None => return Err(String::from("There is no scratchpad in the layout."))
+    };
+
+    let size = match wm_state.tree.try_lookup_element(element_ident)? {
+        Some(element) => match element.profile {
+            LayoutElementProfile::Window(ref window) if element.parent_id == Some(scratchpad_ident) => window.floating.map(|geometry| geometry.size).unwrap_or(Size::zero()),
+            _ => return Err(String::from("The window is not in the scratchpad."))
+        },
+        None => return Err(String::from("That element does not exist in the layout."))
+    };
+
+    let workspace_ident = match wm_state.tree.active_workspace()? {
+        Some((_, workspace_ident)) => workspace_ident,
+        None => return Err(String::from("There is no workspace to show the window in."))
+    };
+    let workspace_geometry = match wm_state.tree.geometry_of(workspace_ident)? {
+        Some(workspace_geometry) => workspace_geometry,
+        None => return Err(String::from("There is no workspace to show the window in."))
+    };
+
+    let centered_geometry = Geometry::new(
+        Point{
+            x: workspace_geometry.origin.x + (workspace_geometry.size.w as i32 - size.w as i32) / 2,
+            y: workspace_geometry.origin.y + (workspace_geometry.size.h as i32 - size.h as i32) / 2
+        },
+        size
+    );
+
+    {
+        let tree = &mut wm_state.tree;
+
+        detach_element(tree, element_ident)?;
+
+        let (slot_ident, relative_geometry) = match seat_floating(tree, workspace_ident, centered_geometry)? {
+            Some(seating) => seating,
+            None => return Err(String::from("The workspace has no floating layer."))
+        };
+
+        seat_element_in_slot(tree, element_ident, slot_ident)?;
+
+        if let Some(mut element) = tree.try_lookup_element(element_ident)? {
+            if let LayoutElementProfile::Window(ref mut window) = element.profile {
+                window.floating = Some(relative_geometry);
+            }
+        }
+    }
+
+    LayoutTree::refresh(wm_state);
+    focus_window(&wm_state.tree, element_ident)?;
+
+    Ok(format!("Showing [{}] from the scratchpad.", element_ident))
+}
+
+/// Shows a hidden window, or hides it if it is shown on the active workspace (elsewhere it is brought over)
+pub fn toggle_scratchpad(wm_state: &mut WMState, element_ident: LayoutElemID) -> Result<String, String>{
+    let in_scratchpad = wm_state.tree.try_lookup_element(element_ident)?.and_then(|element| element.parent_id) == find_scratchpad(wm_state);
+    let on_active_workspace = wm_state.tree.active_workspace()?.map(|(_, ident)| ident) == workspace_of(&wm_state.tree, element_ident)?;
+
+    if in_scratchpad {
+        show_from_scratchpad(wm_state, element_ident)
+    }
+    else if on_active_workspace {
+        hide_in_scratchpad(wm_state, element_ident)
+    }
+    else {
+        hide_in_scratchpad(wm_state, element_ident)?;
+        show_from_scratchpad(wm_state, element_ident)
+    }
+}
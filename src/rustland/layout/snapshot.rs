@@ -0,0 +1,516 @@
+use std::path::PathBuf;
+use std::fs::{File, create_dir_all, read_dir};
+use std::io::{Read, Write};
+use std::env::home_dir;
+use std::collections::HashMap;
+
+use common::definitions::{LayoutElemID, ViewPID};
+use layout::{LayoutTree, PARENT_ELEMENT};
+use layout::arrangement::{find_grid, find_all_windows, seat_element_in_slot, discard_containers};
+use layout::floating::is_floating;
+use layout::element::LayoutElementProfile;
+use layout::element::bisect::{Bisect, Orientation};
+use layout::element::split::Split;
+use layout::element::tabbed::Tabbed;
+use wmstate::WMState;
+
+use serde_json;
+
+/*
+    The container structure of the workspaces, kept on disk apart from the windows occupying it
+*/
+
+#[derive(Serialize, Deserialize, Clone)]
+pub enum SnapshotNode {
+    Unoccupied,
+    Window {
+        app_id: String,
+        // the command that the window was launched by, if it was launched by rustland
+        #[serde(default)]
+        command: Option<String>
+    },
+    Bisect { orientation: Orientation, ratio: f32, children: Vec<SnapshotElement> },
+    Split { orientation: Orientation, weights: Vec<f32>, children: Vec<SnapshotElement> },
+    Tabbed { active_tab: usize, children: Vec<SnapshotElement> }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SnapshotElement {
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub node: SnapshotNode
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct WorkspaceSnapshot {
+    pub index: usize,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub policy: String,
+    pub root: SnapshotElement
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LayoutSnapshot {
+    pub workspaces: Vec<WorkspaceSnapshot>
+}
+
+/// A place kept for an application relaunched by a restored session. The pid is the one of the shell that ran
+/// the command - the application only has the same pid if the shell replaced itself by it, otherwise the first
+/// window of the same app_id takes the place.
+pub struct SessionSeat {
+    pub pid: ViewPID,
+    pub app_id: Option<String>,
+    pub slot_ident: LayoutElemID
+}
+
+// A place for a window in a restored workspace
+struct Slot {
+    workspace_ident: LayoutElemID,
+    slot_ident: LayoutElemID,
+    app_id: Option<String>,
+    command: Option<String>
+}
+
+impl LayoutSnapshot {
+    pub fn directory() -> PathBuf {
+        let mut layouts_path = home_dir().unwrap();
+
+        layouts_path.push(".config");
+        layouts_path.push("rustland");
+        layouts_path.push("layouts");
+
+        return layouts_path;
+    }
+
+    pub fn session_path() -> PathBuf {
+        let mut session_path = home_dir().unwrap();
+
+        session_path.push(".config");
+        session_path.push("rustland");
+        session_path.push("session.json");
+
+        return session_path;
+    }
+
+    pub fn file_path(name: &str) -> Result<PathBuf, String> {
+        if name.is_empty() || name.contains('/') || name.starts_with('.') {
+            return Err(format!("Invalid layout name: '{}'", name));
+        }
+
+        let mut layout_path = Self::directory();
+        layout_path.push(format!("{}.json", name));
+
+        Ok(layout_path)
+    }
+
+    /// The names of the saved layouts
+    pub fn saved_names() -> Vec<String> {
+        let mut names: Vec<String> = match read_dir(Self::directory()) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().map_or(false, |extension| extension == "json"))
+                .filter_map(|path| path.file_stem().and_then(|stem| stem.to_str()).map(|stem| stem.to_string()))
+                .collect(),
+            Err(_) => Vec::new()
+        };
+
+        names.sort();
+        names
+    }
+
+    pub fn capture(wm_state: &WMState) -> Result<LayoutSnapshot, String> {
+        let tree = &wm_state.tree;
+        let workspace_ids = match find_grid(tree, PARENT_ELEMENT)? {
+            Some(grid_ident) => match tree.try_lookup_element(grid_ident)? {
+                Some(grid) => grid.profile.children(),
+                None => Vec::new()
+            },
+            None => Vec::new()
+        };
+
+        let mut workspaces = Vec::new();
+
+        for (index, &workspace_ident) in workspace_ids.iter().enumerate() {
+            let root_ident = match tree.try_lookup_element(workspace_ident)? {
+                Some(workspace) => match workspace.profile {
+                    LayoutElementProfile::Padding(ref padding) => padding.child_elem_id,
+                    _ => continue
+                },
+                None => continue
+            };
+
+            workspaces.push(WorkspaceSnapshot {
+                index: index,
+                tags: tree.tags.persistent_tags(workspace_ident),
+                policy: tree.policy_of(workspace_ident).name().to_string(),
+                root: capture_element(tree, root_ident, &wm_state.launched_commands)?
+            });
+        }
+
+        Ok(LayoutSnapshot { workspaces: workspaces })
+    }
+
+    pub fn save_to_file(&self, layout_file: PathBuf) -> Result<(), String> {
+        let mut parent_directory = layout_file.clone();
+        parent_directory.pop();
+        create_dir_all(parent_directory).map_err(|e| format!("Couldn't create the layout directory: {}", e))?;
+
+        let data = serde_json::to_string_pretty(self).map_err(|e| format!("Couldn't serialize the layout: {}", e))?;
+
+        match File::create(layout_file) {
+            Ok(mut file) => file.write_all(data.as_bytes()).map_err(|e| format!("Couldn't write the layout: {}", e)),
+            Err(e) => Err(format!("Couldn't create layout file: {}", e))
+        }
+    }
+
+    pub fn load_from_file(layout_file: PathBuf) -> Result<LayoutSnapshot, String> {
+        let mut contents = String::new();
+
+        match File::open(layout_file) {
+            Ok(mut file) => { file.read_to_string(&mut contents).map_err(|e| format!("Couldn't read the layout: {}", e))?; },
+            Err(e) => return Err(format!("Couldn't open layout file: {}", e))
+        }
+
+        serde_json::from_str(&contents).map_err(|e| format!("Invalid layout file: {}", e))
+    }
+
+    /// Rebuilds the saved containers in the workspaces - the windows present are seated in them where they fit.
+    /// With 'relaunch', the places of missing windows are reserved for their commands, which are run again.
+    pub fn restore(&self, wm_state: &mut WMState, relaunch: bool) -> Result<String, String> {
+        let (restored, slots, used) = {
+            let &mut WMState{ ref mut tree, ref config, .. } = wm_state;
+
+            let workspace_ids = match find_grid(tree, PARENT_ELEMENT)? {
+                Some(grid_ident) => match tree.try_lookup_element(grid_ident)? {
+                    Some(grid) => grid.profile.children(),
+                    None => return Err(String::from("Grid missing in layout."))
+                },
+                None => return Err(String::from("Grid missing in layout."))
+            };
+
+            // A saved workspace goes to the workspace that carries its name, otherwise to the one in its place
+            let mut targets = Vec::new();
+            for workspace in self.workspaces.iter() {
+                let by_tag = workspace.tags.iter()
+                    .map(|tag| tree.tags.address_element_by_tag(tag.clone()))
+                    .filter(|matches| matches.len() == 1 && workspace_ids.contains(&matches[0]))
+                    .map(|matches| matches[0])
+                    .next();
+
+                match by_tag.or(workspace_ids.get(workspace.index).cloned()) {
+                    Some(workspace_ident) if !targets.iter().any(|&(ident, _)| ident == workspace_ident) => targets.push((workspace_ident, workspace)),
+                    _ => {}
+                }
+            }
+
+            // A preselected place would not survive the restored arrangement
+            let preselection_target = tree.preselection.as_ref().map(|preselection| preselection.target);
+            let mut preselected_here = false;
+            if let Some(target) = preselection_target {
+                for &(workspace_ident, _) in targets.iter() {
+                    preselected_here |= tree.is_descendant_of(target, workspace_ident)?;
+                }
+            }
+            if preselected_here {
+                tree.preselection = None;
+            }
+
+            let mut windows = Vec::new();
+            let mut slots = Vec::new();
+
+            for &(workspace_ident, workspace) in targets.iter() {
+                let former_root_ident = match tree.try_lookup_element(workspace_ident)? {
+                    Some(workspace) => match workspace.profile {
+                        LayoutElementProfile::Padding(ref padding) => padding.child_elem_id,
+                        _ => continue
+                    },
+                    None => continue
+                };
+
+                let mut workspace_windows = Vec::new();
+                find_all_windows(&mut workspace_windows, false, tree, workspace_ident)?;
+
+                let mut tiled = Vec::new();
+                for window_ident in workspace_windows {
+                    if !is_floating(tree, window_ident)? {
+                        tiled.push(window_ident);
+                    }
+                }
+
+                discard_containers(tree, former_root_ident, &tiled)?;
+
+                for &window_ident in tiled.iter() {
+                    tree.set_parent(window_ident, None)?;
+                    windows.push((workspace_ident, window_ident));
+                }
+
+                let root_ident = tree.spawn_dummy_element(Some(workspace_ident))?;
+                if let Some(mut element) = tree.try_lookup_element(workspace_ident)? {
+                    element.profile.replace_child(former_root_ident, root_ident);
+                }
+
+                restore_element(tree, root_ident, &workspace.root, workspace_ident, &mut slots)?;
+
+                if let Some(policy) = config.layout.make_policy(&workspace.policy) {
+                    tree.set_policy_of(workspace_ident, policy);
+                }
+            }
+
+            // Windows go back to a place of the same application first, then to any place left in their workspace
+            let mut used = vec![false; slots.len()];
+
+            for &(workspace_ident, window_ident) in windows.iter() {
+                let app_id = match tree.try_lookup_element(window_ident)? {
+                    Some(element) => match element.profile {
+                        LayoutElementProfile::Window(ref window) => window.get_view().map(|view| view.app_id().to_string()),
+                        _ => None
+                    },
+                    None => None
+                };
+
+                let slot = (0..slots.len()).find(|&i| !used[i] && app_id.is_some() && slots[i].app_id == app_id)
+                    .or((0..slots.len()).find(|&i| !used[i] && slots[i].workspace_ident == workspace_ident));
+
+                let slot_ident = match slot {
+                    Some(i) => {
+                        used[i] = true;
+
+                        // The tags of the place are handed over to the window taking it
+                        let slot_ident = slots[i].slot_ident;
+                        for tag in tree.tags.address_tags_by_element(slot_ident) {
+                            tree.tags.bind_tag(&tag, window_ident);
+                        }
+
+                        slot_ident
+                    },
+                    None => {
+                        let mut policy = tree.policy_of(workspace_ident);
+                        let slot_ident = policy.seat_window(tree, workspace_ident)?;
+                        tree.set_policy_of(workspace_ident, policy);
+
+                        slot_ident
+                    }
+                };
+
+                seat_element_in_slot(tree, window_ident, slot_ident)?;
+            }
+
+            (targets.len(), slots, used)
+        };
+
+        let mut relaunched = 0;
+
+        if relaunch {
+            for (slot, _) in slots.iter().zip(used.iter()).filter(|&(_, &used)| !used) {
+                if let Some(ref command) = slot.command {
+                    match wm_state.launch(command.clone()) {
+                        Ok(pid) => {
+                            wm_state.tree.session_seats.push(SessionSeat {
+                                pid: pid,
+                                app_id: slot.app_id.clone().and_then(|app_id| if app_id.is_empty() { None } else { Some(app_id) }),
+                                slot_ident: slot.slot_ident
+                            });
+                            relaunched += 1;
+                        },
+                        Err(msg) => println!("{}", msg)
+                    }
+                }
+            }
+        }
+
+        LayoutTree::refresh(wm_state);
+
+        match relaunch {
+            true => Ok(format!("Restored the layout of {} workspace(s) and relaunched {} application(s).", restored, relaunched)),
+            false => Ok(format!("Restored the layout of {} workspace(s).", restored))
+        }
+    }
+}
+
+fn capture_element(tree: &LayoutTree, element_ident: LayoutElemID, commands: &HashMap<ViewPID, String>) -> Result<SnapshotElement, String> {
+    let (mut node, child_ids) = match tree.try_lookup_element(element_ident)? {
+        Some(element) => match element.profile {
+            LayoutElementProfile::Window(ref window) => (
+                SnapshotNode::Window {
+                    app_id: window.get_view().map_or(String::new(), |view| view.app_id().to_string()),
+                    command: window.get_view().and_then(|view| commands.get(&view.pid()).cloned())
+                },
+                Vec::new()
+            ),
+            LayoutElementProfile::Bisect(ref bisect) => (
+                SnapshotNode::Bisect { orientation: bisect.orientation, ratio: bisect.ratio, children: Vec::new() },
+                bisect.children_iter().cloned().collect()
+            ),
+            LayoutElementProfile::Split(ref split) => (
+                SnapshotNode::Split { orientation: split.orientation, weights: split.weights().clone(), children: Vec::new() },
+                split.get_all_children().clone()
+            ),
+            LayoutElementProfile::Tabbed(ref tabbed) => (
+                SnapshotNode::Tabbed { active_tab: tabbed.active_tab(), children: Vec::new() },
+                tabbed.get_all_children().clone()
+            ),
+            _ => (SnapshotNode::Unoccupied, Vec::new())
+        },
+        None => (SnapshotNode::Unoccupied, Vec::new())
+    };
+
+    match node {
+        SnapshotNode::Bisect { ref mut children, .. } |
+        SnapshotNode::Split { ref mut children, .. } |
+        SnapshotNode::Tabbed { ref mut children, .. } => {
+            for &child_ident in child_ids.iter() {
+                // Recursion
+                children.push(capture_element(tree, child_ident, commands)?);
+            }
+        },
+        _ => {}
+    }
+
+    Ok(SnapshotElement {
+        tags: tree.tags.persistent_tags(element_ident),
+        node: node
+    })
+}
+
+/// Builds a saved element in place of an unoccupied one, collecting the places for windows in 'slots'
+fn restore_element(tree: &mut LayoutTree, element_ident: LayoutElemID, saved: &SnapshotElement, workspace_ident: LayoutElemID, slots: &mut Vec<Slot>) -> Result<(), String> {
+    for tag in saved.tags.iter() {
+        tree.tags.bind_tag(tag, element_ident);
+    }
+
+    let (child_ids, children) = match saved.node {
+        SnapshotNode::Unoccupied => {
+            slots.push(Slot{ workspace_ident: workspace_ident, slot_ident: element_ident, app_id: None, command: None });
+            return Ok(());
+        },
+        SnapshotNode::Window { ref app_id, ref command } => {
+            slots.push(Slot{ workspace_ident: workspace_ident, slot_ident: element_ident, app_id: Some(app_id.clone()), command: command.clone() });
+            return Ok(());
+        },
+        SnapshotNode::Bisect { orientation, ratio, ref children } if children.len() == 2 => {
+            let (_, bisect) = Bisect::init(element_ident, tree, orientation, ratio)?;
+            let child_ids: Vec<LayoutElemID> = bisect.children_iter().cloned().collect();
+
+            tree.reserve_element_identity(element_ident, LayoutElementProfile::Bisect(bisect))?;
+            (child_ids, children)
+        },
+        SnapshotNode::Bisect { ref children, .. } |
+        SnapshotNode::Split { ref children, .. } if children.is_empty() => {
+            slots.push(Slot{ workspace_ident: workspace_ident, slot_ident: element_ident, app_id: None, command: None });
+            return Ok(());
+        },
+        // A bisect edited to hold another number of children is made a split
+        SnapshotNode::Bisect { orientation, ref children, .. } => {
+            let (_, split) = Split::init(element_ident, tree, orientation, children.len())?;
+            let child_ids = split.get_all_children().clone();
+
+            tree.reserve_element_identity(element_ident, LayoutElementProfile::Split(split))?;
+            (child_ids, children)
+        },
+        SnapshotNode::Split { orientation, ref weights, ref children } => {
+            let (_, mut split) = Split::init(element_ident, tree, orientation, children.len())?;
+            for (i, &weight) in weights.iter().enumerate() {
+                split.set_weight(i, weight);
+            }
+            let child_ids = split.get_all_children().clone();
+
+            tree.reserve_element_identity(element_ident, LayoutElementProfile::Split(split))?;
+            (child_ids, children)
+        },
+        SnapshotNode::Tabbed { ref children, .. } if children.is_empty() => {
+            slots.push(Slot{ workspace_ident: workspace_ident, slot_ident: element_ident, app_id: None, command: None });
+            return Ok(());
+        },
+        SnapshotNode::Tabbed { active_tab, ref children } => {
+            let (_, mut tabbed) = Tabbed::init(element_ident, tree, children.len())?;
+            tabbed.set_active_tab(active_tab);
+            let child_ids = tabbed.get_all_children().clone();
+
+            tree.reserve_element_identity(element_ident, LayoutElementProfile::Tabbed(tabbed))?;
+            (child_ids, children)
+        }
+    };
+
+    for (&child_ident, child) in child_ids.iter().zip(children.iter()) {
+        // Recursion
+        restore_element(tree, child_ident, child, workspace_ident, slots)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test{
+    use std::collections::HashMap;
+    use super::{LayoutSnapshot, WorkspaceSnapshot, SnapshotElement, SnapshotNode, capture_element, restore_element};
+    use layout::LayoutTree;
+    use layout::element::bisect::Orientation;
+    use serde_json;
+    use wlc::{Geometry, Point, Size};
+
+    fn element(tags: Vec<&str>, node: SnapshotNode) -> SnapshotElement {
+        SnapshotElement { tags: tags.into_iter().map(String::from).collect(), node: node }
+    }
+
+    fn window(app_id: &str) -> SnapshotNode {
+        SnapshotNode::Window { app_id: String::from(app_id), command: Some(String::from(app_id)) }
+    }
+
+    // The containers of a workspace, with the windows in it given by 'leaf'
+    fn workspace_root(leaf: &Fn(&str) -> SnapshotNode) -> SnapshotElement {
+        element(vec![], SnapshotNode::Bisect {
+            orientation: Orientation::Horizontal,
+            ratio: 0.3,
+            children: vec![
+                element(vec!["shell"], leaf("termite")),
+                element(vec![], SnapshotNode::Split {
+                    orientation: Orientation::Vertical,
+                    weights: vec![1f32, 2f32],
+                    children: vec![
+                        element(vec![], SnapshotNode::Unoccupied),
+                        element(vec!["media"], SnapshotNode::Tabbed { active_tab: 1, children: vec![element(vec![], leaf("firefox")), element(vec![], leaf("mpv"))] })
+                    ]
+                })
+            ]
+        })
+    }
+
+    #[test]
+    fn snapshot_file_round_trip_test(){
+        let snapshot = LayoutSnapshot {
+            workspaces: vec![WorkspaceSnapshot { index: 2, tags: vec![String::from("dev")], policy: String::from("auto_circulation"), root: workspace_root(&window) }]
+        };
+
+        let data = serde_json::to_string(&snapshot).unwrap();
+        let loaded: LayoutSnapshot = serde_json::from_str(&data).unwrap();
+        assert_eq!(serde_json::to_string(&loaded).unwrap(), data);
+
+        // The command of a window may be left out of the file
+        let loaded: SnapshotElement = serde_json::from_str("{\"node\":{\"Window\":{\"app_id\":\"termite\"}}}").unwrap();
+        match loaded.node {
+            SnapshotNode::Window { ref app_id, ref command } => assert_eq!((app_id.as_str(), command.is_none()), ("termite", true)),
+            _ => panic!("The window wasn't loaded as one.")
+        }
+    }
+
+    #[test]
+    fn snapshot_restore_capture_test(){
+        let mut tree = LayoutTree::init(Geometry{ origin: Point{ x: 0, y: 0 }, size: Size{ w: 1000, h: 500 } }, 1, 1);
+        let workspace_ident = tree.spawn_dummy_element(None).unwrap();
+
+        let mut slots = Vec::new();
+        restore_element(&mut tree, workspace_ident, &workspace_root(&window), workspace_ident, &mut slots).unwrap();
+
+        // Every window and unoccupied element of the snapshot becomes a place to seat a window in
+        let app_ids: Vec<Option<String>> = slots.iter().map(|slot| slot.app_id.clone()).collect();
+        assert_eq!(app_ids, vec![Some(String::from("termite")), None, Some(String::from("firefox")), Some(String::from("mpv"))]);
+        assert!(slots.iter().all(|slot| slot.workspace_ident == workspace_ident));
+
+        // Without their windows, the places are captured as unoccupied - the containers and tags are as saved
+        let captured = capture_element(&tree, workspace_ident, &HashMap::new()).unwrap();
+        let expected = workspace_root(&|_| SnapshotNode::Unoccupied);
+        assert_eq!(serde_json::to_string(&captured).unwrap(), serde_json::to_string(&expected).unwrap());
+    }
+}
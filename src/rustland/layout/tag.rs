@@ -7,7 +7,8 @@ use wmstate::WMState;
 use common::definitions::{LayoutElemID, ViewPID, ElementReference, TAG_PREFIX};
 use common::definitions::ElementReference::*;
 use common::job::Job;
-use wmstate::PENDING_JOBS;
+use common::event::Event;
+use io::event::broadcast;
 use layout::element::LayoutElement;
 use layout::LayoutTree;
 
@@ -99,10 +100,11 @@ impl TagRegister{
                 tag.to_string()
             };
         
-        let tag_elements = self.bindings.entry(unoccupied_tag).or_insert(Vec::new());
+        let tag_elements = self.bindings.entry(unoccupied_tag.clone()).or_insert(Vec::new());
 
         if !tag_elements.contains(&elem_id){
             tag_elements.push(elem_id);
+            broadcast(Event::Tag { tag: unoccupied_tag, element_ids: tag_elements.clone() });
         }
     }
 
@@ -110,18 +112,50 @@ impl TagRegister{
         self.tag_conditions.insert(String::from(tag), Box::new(condition));
     }
 
+    /// Adds an element to a tag as it is named, even if the tag is already in use
+    pub fn bind_tag(&mut self, tag: &str, elem_id: LayoutElemID) {
+        let tag_elements = self.bindings.entry(tag.to_string()).or_insert(Vec::new());
+
+        if !tag_elements.contains(&elem_id){
+            tag_elements.push(elem_id);
+            broadcast(Event::Tag { tag: tag.to_string(), element_ids: tag_elements.clone() });
+        }
+    }
+
+    pub fn is_conditional(&self, tag: &str) -> bool {
+        self.tag_conditions.contains_key(tag)
+    }
+
+    /// The tags of an element given by name - not the ones of windows by their identity, or the ones given on a condition
+    pub fn persistent_tags(&self, elem_id: LayoutElemID) -> Vec<String> {
+        self.address_tags_by_element(elem_id).into_iter()
+            .filter(|tag| tag.parse::<LayoutElemID>().is_err() && !self.is_conditional(tag))
+            .collect()
+    }
+
+    /// Forgets the tags that no element carries any longer, so that their names can be given out again
+    pub fn prune_unused(&mut self) {
+        let tag_conditions = &self.tag_conditions;
+        self.bindings.retain(|tag, tag_elements| !tag_elements.is_empty() || tag_conditions.contains_key(tag));
+    }
+
     pub fn untag_element(&mut self, elem_id: LayoutElemID){
         for (tag, mut tag_elements) in &mut self.bindings{
-            match tag_elements.binary_search(&elem_id)
+            match tag_elements.iter().position(|&e| e == elem_id)
             {
-                Ok(index) => { tag_elements.remove(index); },
+                Some(index) => { 
+                    tag_elements.remove(index); 
+                    broadcast(Event::Tag { tag: tag.clone(), element_ids: tag_elements.clone() });
+                },
                 _ => {}
             }
         }
     }
 
     pub fn remove_tag(&mut self, tag: &str, include_conditions: bool){
-        self.bindings.remove(&tag.to_string());
+        if self.bindings.remove(&tag.to_string()).is_some() {
+            broadcast(Event::Tag { tag: tag.to_string(), element_ids: Vec::new() });
+        }
     }
 
     pub fn refresh_tag_statuses(wm_state: &mut WMState){
@@ -130,6 +164,8 @@ impl TagRegister{
         for elem_id in wm_state.tree.get_all_element_ids(){
             elements_ids.push(elem_id.clone())
         }
+
+        let mut changed_tags = Vec::<String>::new();
         
         for (tag, det) in &wm_state.tree.tags.tag_conditions {
             for candidate_id in &elements_ids{
@@ -138,6 +174,7 @@ impl TagRegister{
 
                     if !tag_elements.contains(&candidate_id) {
                         tag_elements.push(*candidate_id);
+                        changed_tags.push(tag.clone());
                     }
                 }
                 else {
@@ -145,12 +182,22 @@ impl TagRegister{
 
                     match tag_elements.binary_search(&candidate_id)
                     {
-                        Ok(index) => { tag_elements.remove(index); },
+                        Ok(index) => { 
+                            tag_elements.remove(index); 
+                            changed_tags.push(tag.clone());
+                        },
                         _ => {}
                     }
                 }
             }
         }
+
+        changed_tags.sort();
+        changed_tags.dedup();
+        for tag in changed_tags {
+            let element_ids = wm_state.tree.tags.address_element_by_tag(tag.clone());
+            broadcast(Event::Tag { tag: tag, element_ids: element_ids });
+        }
     }
 
     pub fn handle_element_swap(&mut self, elem1_id: LayoutElemID, elem2_id: LayoutElemID){
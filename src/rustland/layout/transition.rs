@@ -36,14 +36,19 @@ impl Transition{
         }
         else {
             self.interpolation.intervals = self.time_frame_ms / time_delta_ms;
-            if let Some(ref mut elem) = tree.lookup_element(self.element_id){
-                let mut new_value = 0f32;
-                let result = self.interpolation.next(&mut new_value);
-                elem.set_property(self.transitioning_property.clone(), new_value);
-                result
-            }
-            else{
-                panic!("Invalid element!");  
+            match tree.try_lookup_element(self.element_id) {
+                Ok(Some(ref mut elem)) => {
+                    let mut new_value = 0f32;
+                    let result = self.interpolation.next(&mut new_value);
+                    elem.set_property(self.transitioning_property.clone(), new_value);
+                    result
+                },
+                Ok(None) => panic!("Invalid element!"),
+                Err(msg) => {
+                    // The element is in use - the transition carries on with the next frame
+                    println!("{}", msg);
+                    true
+                }
             }
         }
         
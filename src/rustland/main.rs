@@ -17,6 +17,8 @@ pub extern crate gl;
 pub extern crate thread_tryjoin;
 pub extern crate serde;
 pub extern crate toml;
+pub extern crate regex;
+pub extern crate libc;
 
 extern crate common;
 
@@ -46,6 +46,7 @@ pub trait GeometryExt {
     fn overlaps_geometry(&self, other_geom: Geometry) -> bool;
     fn contains_point(&self, point: Point) -> bool;
     fn scaled(&mut self, x_factor: f32, y_factor: f32) -> Geometry;
+    fn interpolated(&self, target: Geometry, progress: f32) -> Geometry;
 }
 
 impl GeometryExt for Geometry {
@@ -86,4 +87,19 @@ impl GeometryExt for Geometry {
             }
         } 
     }
+
+    fn interpolated(&self, target: Geometry, progress: f32) -> Geometry{
+        let step = |from: f32, to: f32| from + (to - from) * progress;
+
+        Geometry {
+            origin: Point {
+                x: step(self.origin.x as f32, target.origin.x as f32) as i32,
+                y: step(self.origin.y as f32, target.origin.y as f32) as i32
+            },
+            size: Size {
+                w: step(self.size.w as f32, target.size.w as f32) as u32,
+                h: step(self.size.h as f32, target.size.h as f32) as u32
+            }
+        }
+    }
 }
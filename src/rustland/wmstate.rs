@@ -1,16 +1,21 @@
 use std::sync::{RwLock, Mutex};
+use std::sync::mpsc::Sender;
 use std::marker::Sync;
 use std::cell::{RefCell, RefMut};
 use std::fs::File;
 use std::thread::JoinHandle;
+use std::process::Command;
 use std::sync::atomic::AtomicBool;
+use std::collections::HashMap;
+use std::time::Instant;
 
-use common::definitions::FALLBACK_RESOLUTION;
+use common::definitions::{FALLBACK_RESOLUTION, ViewPID, LayoutElemID};
 use common::job::Job;
 use config::Config;
 use io::physical::InputDevice;
 use layout::transition::Transition;
 use layout::*;
+use layout::arrangement;
 use layout::element::LayoutElementProfile;
 use layout::element::padding::Padding;
 use layout::tag::TagRegister;
@@ -34,7 +39,10 @@ pub struct WMState{
     pub graphics_program: Option<GraphicsProgram>,
     wallpaper: Option<Wallpaper>,
     pub solid_color: Option<SolidColor>,
-    pub next_wallpaper_image: Option<JoinHandle<RgbaImage>>
+    pub next_wallpaper_image: Option<JoinHandle<RgbaImage>>,
+
+    // the commands that processes were launched with by the compositor
+    pub launched_commands: HashMap<ViewPID, String>
 }
 
 impl WMState {
@@ -68,14 +76,64 @@ impl WMState {
         println!("Wallpaper set."); 
     }
 
+    /// Runs a shell command and remembers it as the launching command of the process.
+    /// The pid is the one of the shell - the application has it too only if the shell replaced itself by it.
+    pub fn launch(&mut self, command: String) -> Result<ViewPID, String>{
+        match Command::new("sh").arg("-c").arg(&command).spawn() {
+            Ok(child) => {
+                let pid = child.id() as ViewPID;
+                self.launched_commands.insert(pid, command);
+                Ok(pid)
+            },
+            Err(e) => Err(format!("Can't spawn process: {}", e))
+        }
+    }
+
+    /// Animates the jumper so that the active workspace of the grid fills the display
+    pub fn jump_to_active_workspace(&mut self, animation_time: u64){
+        let display_geometry = self.tree.get_outer_geometry();
+
+        let workspace_offset = match arrangement::find_grid(&self.tree, PARENT_ELEMENT).and_then(|grid| match grid {
+            Some(grid_ident) => self.tree.try_lookup_element(grid_ident),
+            None => Ok(None)
+        }) {
+            Ok(Some(element)) => match element.profile {
+                LayoutElementProfile::Grid(ref grid) => Some(grid.get_offset_geometry(display_geometry, Geometry::zero(), grid.active_subspace() as u16, &mut (1.0f32, 1.0f32))),
+                _ => None
+            },
+            Ok(None) => None,
+            Err(e) => {
+                println!("Can't jump to the active workspace: {}", e);
+                None
+            }
+        };
+
+        if let Some(&jumper_ident) = self.tree.tags.address_element_by_tag(self.config.layout.jumper_tag.clone()).first() {
+            if let Some(geometry) = workspace_offset{
+                let jumped = self.tree.animate_property(jumper_ident, "offset_x", -geometry.origin.x as f32, false, animation_time)
+                    .and_then(|_| self.tree.animate_property(jumper_ident, "offset_y", -geometry.origin.y as f32, false, animation_time));
+
+                if let Err(e) = jumped {
+                    println!("Can't jump to the active workspace: {}", e);
+                }
+            }
+        }
+    }
+
     pub fn render_background(&mut self){
         if let Some(ref mut program) = self.graphics_program {
             let mut scale = (1.0f32, 1.0f32);    
             
             let total_geom = self.tree.get_outer_geometry();
-            let geometry = match self.tree.lookup_element_by_tag(self.config.layout.jumper_tag.clone()).first().expect("No jumper!").profile{
-                LayoutElementProfile::Padding(ref root) => root.get_offset_geometry(self.tree.get_outer_geometry(), &mut scale),
-                _ => total_geom
+            let geometry = match self.tree.lookup_element_by_tag(self.config.layout.jumper_tag.clone()) {
+                Ok(jumpers) => match jumpers.first().expect("No jumper!").profile{
+                    LayoutElementProfile::Padding(ref root) => root.get_offset_geometry(self.tree.get_outer_geometry(), &mut scale),
+                    _ => total_geom
+                },
+                Err(msg) => {
+                    println!("{}", msg);
+                    total_geom
+                }
             };
             
 
@@ -103,13 +161,17 @@ lazy_static! {
             graphics_program: None,
             wallpaper: None,
             solid_color: None,
-            next_wallpaper_image: None
+            next_wallpaper_image: None,
+            launched_commands: HashMap::new()
         }
     );
 
-    pub static ref PENDING_JOBS: Mutex<Vec<Job> >= Mutex::new(Vec::new());
-    pub static ref FINALIZED_JOBS: Mutex<Vec<Job>> = Mutex::new(Vec::new()); 
+    // Jobs waiting to be processed, along with where to send the result (if anywhere)
+    pub static ref PENDING_JOBS: Mutex<Vec<(Job, Option<Sender<Job>>)>> = Mutex::new(Vec::new());
     pub static ref ACTIVE_TRANSITIONS: Mutex<Vec<Transition>> = Mutex::new(Vec::new());
+
+    // When the layout is to be compacted, pushed back by every window closed in the meantime
+    pub static ref PENDING_COMPACTION: Mutex<Option<Instant>> = Mutex::new(None);
 }
 
 unsafe impl Send for ACTIVE_TRANSITIONS {}